@@ -0,0 +1,117 @@
+//! Named commands and the fuzzy matching behind the command palette.
+//!
+//! Every action `app` can dispatch — from a menu click, a keybinding, or a
+//! palette selection — has one entry here instead of being wired directly
+//! into the render tree three separate ways. Adding a new capability (the
+//! way `diagnostics`/`tabs` did) means adding a `CommandId` variant and a
+//! `COMMANDS` entry, not hunting down every place that needs to know about it.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CommandId {
+    NewTab,
+    Open,
+    Save,
+    SaveAs,
+    CloseTab,
+    ExportSvg,
+    ToggleLogs,
+    FocusHighlight,
+    ReformatModel,
+}
+
+/// A keyboard shortcut a command can be bound to, matched against
+/// `KeyboardEvent::modifiers()`/`key()` in `app`'s root `onkeydown`.
+#[derive(Clone, Copy, Debug)]
+pub struct Keybinding {
+    pub ctrl: bool,
+    pub shift: bool,
+    /// Lowercase, compared case-insensitively against `Key`'s `Display` form
+    /// (e.g. `"o"`, `"s"`) rather than a specific `keyboard_types::Key`
+    /// variant, the same Debug/Display-based fallback `editor.rs` uses for
+    /// `Token` — it's one string comparison instead of a match arm per key.
+    pub key: &'static str,
+}
+
+impl Keybinding {
+    pub const fn new(ctrl: bool, shift: bool, key: &'static str) -> Self {
+        Keybinding { ctrl, shift, key }
+    }
+
+    pub fn matches(&self, ctrl: bool, shift: bool, key: &str) -> bool {
+        self.ctrl == ctrl && self.shift == shift && self.key.eq_ignore_ascii_case(key)
+    }
+
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.ctrl {
+            parts.push("Ctrl".to_string());
+        }
+        if self.shift {
+            parts.push("Shift".to_string());
+        }
+        parts.push(self.key.to_uppercase());
+        parts.join("+")
+    }
+}
+
+pub struct Command {
+    pub id: CommandId,
+    pub title: &'static str,
+    pub keybinding: Option<Keybinding>,
+}
+
+pub const COMMANDS: &[Command] = &[
+    Command { id: CommandId::Open, title: "Open...", keybinding: Some(Keybinding::new(true, false, "o")) },
+    Command { id: CommandId::Save, title: "Save", keybinding: Some(Keybinding::new(true, false, "s")) },
+    Command { id: CommandId::SaveAs, title: "Save As...", keybinding: None },
+    Command { id: CommandId::NewTab, title: "New Tab", keybinding: None },
+    Command { id: CommandId::CloseTab, title: "Close Tab", keybinding: None },
+    Command { id: CommandId::ExportSvg, title: "Export SVG", keybinding: Some(Keybinding::new(true, true, "e")) },
+    Command { id: CommandId::ToggleLogs, title: "Toggle debug logs", keybinding: Some(Keybinding::new(true, true, "l")) },
+    Command { id: CommandId::FocusHighlight, title: "Focus highlight box", keybinding: Some(Keybinding::new(true, true, "h")) },
+    Command { id: CommandId::ReformatModel, title: "Reformat model", keybinding: Some(Keybinding::new(true, true, "f")) },
+];
+
+/// The command bound to this chord, if any.
+pub fn command_for_key(ctrl: bool, shift: bool, key: &str) -> Option<CommandId> {
+    COMMANDS.iter().find(|c| c.keybinding.map_or(false, |kb| kb.matches(ctrl, shift, key))).map(|c| c.id)
+}
+
+/// Subsequence fuzzy match: every character of `query` must appear in
+/// `text`, in order, case-insensitively. Returns a score (higher is a
+/// better match) when it does, `None` otherwise. Contiguous runs and
+/// matches right after a space score a bonus, so e.g. `"exp"` ranks
+/// "Export SVG" above a command that only contains those three letters
+/// scattered further apart.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let haystack: Vec<char> = text.to_lowercase().chars().collect();
+    let needle: Vec<char> = query.to_lowercase().chars().collect();
+
+    let mut score = 0;
+    let mut cursor = 0;
+    let mut prev_match: Option<usize> = None;
+    for &qc in &needle {
+        let found = haystack[cursor..].iter().position(|&c| c == qc).map(|i| i + cursor)?;
+        score += 1;
+        if prev_match == Some(found.wrapping_sub(1)) {
+            score += 3;
+        }
+        if found == 0 || haystack.get(found - 1) == Some(&' ') {
+            score += 2;
+        }
+        prev_match = Some(found);
+        cursor = found + 1;
+    }
+    Some(score)
+}
+
+/// Every command whose title fuzzy-matches `query`, best match first.
+pub fn filter_commands(query: &str) -> Vec<&'static Command> {
+    let mut scored: Vec<(i32, &'static Command)> =
+        COMMANDS.iter().filter_map(|c| fuzzy_match(query, c.title).map(|score| (score, c))).collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored.into_iter().map(|(_, c)| c).collect()
+}
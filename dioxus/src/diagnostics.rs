@@ -0,0 +1,54 @@
+//! In-editor diagnostics: errors from `draw`/`parse_highlights` kept in app
+//! state and rendered as a strip plus gutter markers, instead of only going
+//! to the trace log.
+//!
+//! `depict::graph_drawing::error::Error` lives in the `depict` library, not
+//! this crate, so once a failure has been wrapped into one we can't
+//! pattern-match it back into its `Kind` variants to recover the byte span
+//! it was built from. The one place that span is still ours to keep is
+//! `parse_highlights`, which constructs `Kind::PomeloError { span, .. }`
+//! itself — see its call site in `main.rs`, which carries that span out
+//! alongside the `Error` instead of letting it get erased. `draw`'s own
+//! failures don't get that treatment (there's no construction site here to
+//! hook), so they're anchored to the whole buffer until `depict` exposes a
+//! way to recover a span from an already-built `Error`.
+
+use std::ops::Range;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, span: Range<usize>, message: impl Into<String>) -> Self {
+        Diagnostic { severity, span, message: message.into() }
+    }
+
+    /// A diagnostic with no span information narrower than "the whole
+    /// buffer" available — see the module doc comment.
+    pub fn whole_buffer(severity: Severity, len: usize, message: impl Into<String>) -> Self {
+        Diagnostic::new(severity, 0..len, message)
+    }
+
+    pub fn class(&self) -> &'static str {
+        match self.severity {
+            Severity::Error => "diagnostic-error",
+            Severity::Warning => "diagnostic-warning",
+        }
+    }
+}
+
+/// 0-based line number of the byte offset `at` within `text`, for placing a
+/// diagnostic's gutter marker next to the line it starts on.
+pub fn line_of(text: &str, at: usize) -> usize {
+    text.as_bytes()[..at.min(text.len())].iter().filter(|&&b| b == b'\n').count()
+}
@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// The full state of one editing session, serialized as the contents of a
+/// saved `.depict` file so reopening a document restores more than just the
+/// raw model text.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Document {
+    pub model: String,
+    pub highlight: String,
+    pub viewbox_width: f64,
+    pub viewbox_height: f64,
+}
+
+impl Document {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+pub fn pick_open_path() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Depict document", &["depict"])
+        .pick_file()
+}
+
+pub fn pick_save_path() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Depict document", &["depict"])
+        .set_file_name("untitled.depict")
+        .save_file()
+}
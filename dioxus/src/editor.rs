@@ -0,0 +1,203 @@
+//! Token-level syntax highlighting for the model editor.
+//!
+//! Lexes the buffer with the same `depict::parser::Token`/`logos` lexer
+//! `parse_highlights` already drives, and turns the resulting token spans
+//! into a backdrop `<pre>` of classed `<span>`s that sits behind a
+//! transparent `<textarea>` in `app` — the textarea keeps owning focus,
+//! selection and editing, while the backdrop supplies the color.
+//!
+//! `HighlightCache` is what keeps this from relexing the whole buffer on
+//! every keystroke: `oninput` only hands us the buffer's new full text, not
+//! the edit itself, so `update` diffs it against the previously cached text
+//! (a common-prefix scan from the start of the buffer, which locates the
+//! touched line) and, so long as the
+//! edit didn't touch line structure, only relexes that one line and shifts
+//! the cached spans on either side of it. `logos::Lexer` has no resumable
+//! API, so any edit that crosses a line boundary (or whose surrounding text
+//! shifted) still falls back to a full relex — correct in all cases, just
+//! not always incremental.
+
+use depict::parser::Token;
+use dioxus::prelude::*;
+use logos::Logos;
+
+/// One lexed token's byte range and the CSS class its span should carry.
+#[derive(Clone, Debug)]
+pub struct HighlightSpan {
+    pub start: usize,
+    pub end: usize,
+    pub class: String,
+}
+
+/// `Token` isn't ours to pattern-match exhaustively from this crate, so we
+/// fall back to its `Debug` form the same way `pretty::print_directive`
+/// does for `Directive` on the `dot` side: `Ident("foo")` becomes the class
+/// `token-ident`.
+fn token_class(token: &Token) -> String {
+    let debug = format!("{token:?}");
+    let name = debug.split(['(', ' ']).next().unwrap_or("unknown");
+    format!("token-{}", name.to_ascii_lowercase())
+}
+
+/// Lexes the whole of `text` into spans. `HighlightCache` is what avoids
+/// calling this on every keystroke; call it directly for a fresh buffer, or
+/// let `HighlightCache::update` fall back to it when an edit can't be
+/// patched in place.
+pub fn highlight(text: &str) -> Vec<HighlightSpan> {
+    let mut lex = Token::lexer(text);
+    let mut spans = Vec::new();
+    while let Some(token) = lex.next() {
+        let span = lex.span();
+        spans.push(HighlightSpan { class: token_class(&token), start: span.start, end: span.end });
+    }
+    spans
+}
+
+/// The byte offset of the first difference between `old` and `new`, found
+/// by counting matching leading `char`s — a valid char boundary in both
+/// strings, since it sits right after a run of chars common to both.
+fn first_difference(old: &str, new: &str) -> usize {
+    let mut at = 0;
+    for (a, b) in old.chars().zip(new.chars()) {
+        if a != b {
+            return at;
+        }
+        at += a.len_utf8();
+    }
+    at
+}
+
+/// The byte range `[start, end)` of the line containing offset `at` in
+/// `text`, excluding the trailing newline (if any).
+fn line_bounds(text: &str, at: usize) -> (usize, usize) {
+    let start = text[..at].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let end = text[at..].find('\n').map(|i| at + i).unwrap_or(text.len());
+    (start, end)
+}
+
+/// The text and spans a backdrop render was last built from, updated
+/// incrementally as the buffer changes rather than relexed wholesale.
+#[derive(Clone, Default)]
+pub struct HighlightCache {
+    text: String,
+    spans: Vec<HighlightSpan>,
+}
+
+impl HighlightCache {
+    pub fn spans(&self) -> &[HighlightSpan] {
+        &self.spans
+    }
+
+    /// Brings this cache up to date with `new_text`. Finds the first byte
+    /// where `new_text` diverges from the previously cached text, and if
+    /// everything after that line is still byte-for-byte identical (i.e.
+    /// the edit didn't touch line structure), relexes only the one changed
+    /// line and shifts the spans that followed it by the line's length
+    /// delta. Otherwise falls back to relexing `new_text` in full, same as
+    /// before this cache existed.
+    pub fn update(&mut self, new_text: &str) {
+        if self.text == new_text {
+            return;
+        }
+
+        let at = first_difference(&self.text, new_text).min(self.text.len()).min(new_text.len());
+        let (line_start, old_line_end) = line_bounds(&self.text, at);
+        let (_, new_line_end) = line_bounds(new_text, at);
+
+        let same_line_edit = !new_text[line_start..new_line_end].contains('\n')
+            && self.text[old_line_end..] == new_text[new_line_end..];
+
+        if same_line_edit {
+            let mut spans: Vec<HighlightSpan> =
+                self.spans.iter().filter(|s| s.end <= line_start).cloned().collect();
+            spans.extend(highlight(&new_text[line_start..new_line_end]).into_iter().map(|s| HighlightSpan {
+                start: s.start + line_start,
+                end: s.end + line_start,
+                class: s.class,
+            }));
+            let shift = new_line_end as isize - old_line_end as isize;
+            spans.extend(self.spans.iter().filter(|s| s.start >= old_line_end).map(|s| HighlightSpan {
+                start: (s.start as isize + shift) as usize,
+                end: (s.end as isize + shift) as usize,
+                class: s.class.clone(),
+            }));
+            self.spans = spans;
+        } else {
+            self.spans = highlight(new_text);
+        }
+
+        self.text = new_text.to_string();
+    }
+}
+
+/// Foreground colors cycled across whatever distinct `token-*` classes
+/// actually occur in a buffer, picked by hashing the class name. `Token`'s
+/// full variant set isn't enumerable from this crate (see `token_class`),
+/// so these can't be assigned by meaning ("keyword" vs. "arrow") the way a
+/// real syntax theme would. Distinct token classes usually get distinct
+/// colors, but not always: two classes whose hashes agree modulo
+/// `TOKEN_COLORS.len()` share a color.
+const TOKEN_COLORS: &[(u8, u8, u8)] = &[
+    (207, 34, 46),
+    (5, 80, 174),
+    (140, 90, 0),
+    (20, 120, 60),
+    (110, 40, 150),
+    (30, 100, 110),
+];
+
+fn token_color(class: &str) -> (u8, u8, u8) {
+    let hash = class.bytes().fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32));
+    TOKEN_COLORS[hash as usize % TOKEN_COLORS.len()]
+}
+
+/// One CSS color rule per distinct class among `spans`, for a `<style>` tag
+/// rendered alongside the backdrop built from those same spans.
+pub fn token_style_css(spans: &[HighlightSpan]) -> String {
+    let mut seen = std::collections::BTreeSet::new();
+    let mut css = String::new();
+    for span in spans {
+        if seen.insert(span.class.clone()) {
+            let (r, g, b) = token_color(&span.class);
+            css.push_str(&format!(".{} {{ color: rgb({r}, {g}, {b}); }}\n", span.class));
+        }
+    }
+    css
+}
+
+/// Renders `text` as a `<pre class="editor-backdrop">` with each of `spans`
+/// wrapped in a `<span class="token-...">`; the untokenized bytes in
+/// between (mostly whitespace) are emitted as plain text so the backdrop's
+/// rendered text exactly matches the textarea's, keeping the two layers
+/// aligned. `spans` comes from the caller's `HighlightCache` rather than
+/// being relexed here, so switching tabs or re-rendering for unrelated
+/// state changes doesn't re-lex anything.
+pub fn render_backdrop<P>(cx: Scope<P>, text: &str, spans: &[HighlightSpan]) -> Option<VNode> {
+    let mut children = Vec::new();
+    let mut pos = 0;
+    for s in spans {
+        if s.start > pos {
+            let before = &text[pos..s.start];
+            children.push(rsx!{ "{before}" });
+        }
+        let slice = &text[s.start..s.end];
+        let class = s.class.clone();
+        children.push(rsx!{
+            span {
+                class: "{class}",
+                "{slice}"
+            }
+        });
+        pos = s.end;
+    }
+    if pos < text.len() {
+        let rest = &text[pos..];
+        children.push(rsx!{ "{rest}" });
+    }
+    cx.render(rsx!{
+        pre {
+            class: "editor-backdrop",
+            children.into_iter()
+        }
+    })
+}
@@ -3,6 +3,20 @@ use std::cell::Cell;
 use std::io::{self};
 use std::panic::catch_unwind;
 
+mod document;
+use document::Document;
+mod editor;
+mod diagnostics;
+use diagnostics::{Diagnostic, Severity};
+mod theme;
+use theme::Theme;
+mod tabs;
+use tabs::Tab;
+mod commands;
+use commands::CommandId;
+
+use ropey::Rope;
+
 use depict::graph_drawing::error::{Error, OrErrExt, Kind};
 use depict::graph_drawing::eval::{Val, Body};
 use depict::graph_drawing::frontend::log::Record;
@@ -34,7 +48,19 @@ k [ - s b ]
 - c s
 ");
 
+/// `MenuId`s for the File menu's custom items, assembled in `main` and
+/// handed to `app` so its `use_wry_event_handler` can match on them.
+#[derive(Clone, Copy)]
+pub struct FileMenuIds {
+    pub new: tao::menu::MenuId,
+    pub open: tao::menu::MenuId,
+    pub save: tao::menu::MenuId,
+    pub save_as: tao::menu::MenuId,
+    pub close_tab: tao::menu::MenuId,
+}
+
 pub struct AppProps {
+    pub file_menu: FileMenuIds,
 }
 
 pub fn render_one<P>(cx: Scope<P>, record: Record) -> Option<VNode> {
@@ -113,13 +139,18 @@ pub fn render_logs<P>(cx: Scope<P>, drawing: Drawing) -> Option<VNode> {
     })
 }
 
-pub fn parse_highlights<'s>(data: &'s str) -> Result<Val<Cow<'s, str>>, Error> {
+/// Like [`Error`], but with the byte span `Kind::PomeloError` was built from
+/// carried alongside it instead of erased — the one `Error`-producing step
+/// here that still has a real source span in hand. See `diagnostics.rs`.
+pub type HighlightError = (Error, Option<std::ops::Range<usize>>);
+
+pub fn parse_highlights<'s>(data: &'s str) -> Result<Val<Cow<'s, str>>, HighlightError> {
     use depict::parser::{Parser, Token};
     use depict::graph_drawing::eval::{eval, index, resolve};
     use logos::Logos;
     use std::collections::HashMap;
     use tracing_error::InstrumentResult;
-    
+
     if data.trim().is_empty() {
         return Ok(Val::default())
     }
@@ -132,17 +163,22 @@ pub fn parse_highlights<'s>(data: &'s str) -> Result<Val<Cow<'s, str>>, Error> {
     }
     let mut lex = Token::lexer(data);
     while let Some(tk) = lex.next() {
+        let span = lex.span();
         p.parse(tk)
             .map_err(|_| {
-                Kind::PomeloError{span: lex.span(), text: lex.slice().into()}
+                Kind::PomeloError{span: span.clone(), text: lex.slice().into()}
             })
-            .in_current_span()?
+            .in_current_span()
+            .map_err(|e| (e, Some(span)))?
     }
 
+    let end_span = lex.span();
     let items = p.end_of_input()
         .map_err(|_| {
-            Kind::PomeloError{span: lex.span(), text: lex.slice().into()}
-        })?;
+            Kind::PomeloError{span: end_span.clone(), text: lex.slice().into()}
+        })
+        .in_current_span()
+        .map_err(|e| (e, Some(end_span)))?;
 
     event!(Level::TRACE, ?items, "HIGHLIGHT PARSE");
     eprintln!("HIGHLIGHT PARSE {items:#?}");
@@ -162,26 +198,204 @@ pub fn parse_highlights<'s>(data: &'s str) -> Result<Val<Cow<'s, str>>, Error> {
     Ok(val)
 }
 
+/// These, plus `show_logs.modify`/the `ExportSvg`/`FocusHighlight` bodies in
+/// `run_command`, are `app`'s whole command surface: the File menu, the
+/// keymap and the command palette all call into the same handful of
+/// functions instead of each re-implementing "open a file" or "close the
+/// active tab" their own way.
+fn new_tab(tabs: &UseState<Vec<Tab>>, active: &UseState<usize>) {
+    let mut v = tabs.get().clone();
+    let name = tabs::next_untitled_name(&v);
+    v.push(Tab::untitled(name));
+    active.set(v.len() - 1);
+    tabs.set(v);
+}
+
+fn open_document(tabs: &UseState<Vec<Tab>>, active: &UseState<usize>) {
+    if let Some(path) = document::pick_open_path() {
+        match Document::load(&path) {
+            Ok(doc) => {
+                let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| "Untitled".to_string());
+                let mut v = tabs.get().clone();
+                v.push(Tab::from_document(name, doc, path));
+                active.set(v.len() - 1);
+                tabs.set(v);
+            },
+            Err(err) => event!(Level::ERROR, ?err, "FAILED TO OPEN DOCUMENT"),
+        }
+    }
+}
+
+fn save_document(tabs: &UseState<Vec<Tab>>, active: &UseState<usize>, save_as: bool) {
+    let idx = *active.get();
+    let mut v = tabs.get().clone();
+    let Some(tab) = v.get_mut(idx) else { return };
+    let path = if save_as {
+        document::pick_save_path()
+    } else {
+        tab.current_path.clone().or_else(document::pick_save_path)
+    };
+    if let Some(path) = path {
+        let doc = Document {
+            model: tab.model.to_string(),
+            highlight: tab.highlight.clone(),
+            viewbox_width: tab.drawing.viewbox_width,
+            viewbox_height: tab.drawing.viewbox_height,
+        };
+        match doc.save(&path) {
+            Ok(()) => {
+                tab.current_path = Some(path);
+                tab.dirty = false;
+                tabs.set(v);
+            },
+            Err(err) => event!(Level::ERROR, ?err, "FAILED TO SAVE DOCUMENT"),
+        }
+    }
+}
+
+fn close_tab(tabs: &UseState<Vec<Tab>>, active: &UseState<usize>) {
+    let mut v = tabs.get().clone();
+    if v.len() > 1 {
+        let idx = *active.get();
+        v.remove(idx);
+        active.set(idx.min(v.len() - 1));
+        tabs.set(v);
+    }
+}
+
+/// Trims trailing whitespace from each line and collapses runs of blank
+/// lines to one. `depict`'s grammar isn't exposed to this crate as an AST
+/// we could round-trip through a real pretty-printer — see `editor.rs`'s
+/// token-class fallback for the same "not ours to introspect" shape — so
+/// this is whitespace cleanup, not true canonicalization.
+fn reformat(text: &str) -> String {
+    let mut out = String::new();
+    let mut blank_run = false;
+    for line in text.lines() {
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            if blank_run {
+                continue;
+            }
+            blank_run = true;
+        } else {
+            blank_run = false;
+        }
+        out.push_str(trimmed);
+        out.push('\n');
+    }
+    out
+}
+
+fn reformat_model(tabs: &UseState<Vec<Tab>>, active: &UseState<usize>) {
+    let idx = *active.get();
+    let mut v = tabs.get().clone();
+    if let Some(tab) = v.get_mut(idx) {
+        let reformatted = reformat(&tab.model.to_string());
+        tab.model = Rope::from_str(&reformatted);
+        tab.dirty = true;
+        tabs.set(v);
+    }
+}
+
+/// Runs `id`, the one dispatch point the keymap and the command palette
+/// funnel through, and closes the palette afterward (a no-op if it wasn't
+/// open, e.g. for a keybinding). The File menu's `use_wry_event_handler`
+/// calls the helper functions above directly rather than going through here.
+fn run_command(
+    id: CommandId,
+    tabs: &UseState<Vec<Tab>>,
+    active: &UseState<usize>,
+    show_logs: &UseState<bool>,
+    desktop: &Option<dioxus_desktop::desktop_context::DesktopContext>,
+    palette_open: &UseState<bool>,
+) {
+    match id {
+        CommandId::NewTab => new_tab(tabs, active),
+        CommandId::Open => open_document(tabs, active),
+        CommandId::Save => save_document(tabs, active, false),
+        CommandId::SaveAs => save_document(tabs, active, true),
+        CommandId::CloseTab => close_tab(tabs, active),
+        CommandId::ToggleLogs => show_logs.modify(|v| !v),
+        CommandId::ReformatModel => reformat_model(tabs, active),
+        CommandId::ExportSvg => {
+            if let Some(desktop) = desktop {
+                desktop.eval("const a = document.querySelector('a[download]'); if (a) { a.click(); }");
+            }
+        },
+        CommandId::FocusHighlight => {
+            if let Some(desktop) = desktop {
+                desktop.eval("const el = document.querySelector('.highlight-input'); if (el) { el.focus(); }");
+            }
+        },
+    }
+    palette_open.set(false);
+}
+
 pub fn app(cx: Scope<AppProps>) -> Element {
-    let model = use_state(&cx, || String::from(PLACEHOLDER));
-    let drawing = use_state(&cx, Drawing::default);
-    let highlight = use_state(&cx, || String::from(""));
+    let tabs = use_state(&cx, || vec![{
+        let mut tab = Tab::untitled("Untitled");
+        tab.model = Rope::from_str(PLACEHOLDER);
+        tab
+    }]);
+    let active = use_state(&cx, || 0usize);
+    let renaming = use_state::<Option<usize>>(&cx, || None);
+    let palette_open = use_state(&cx, || false);
+    let palette_query = use_state(&cx, || String::new());
+    let highlight_caches = use_ref(&cx, || std::collections::HashMap::<usize, editor::HighlightCache>::new());
+
+    let desktop = cx.consume_context::<dioxus_desktop::desktop_context::DesktopContext>();
+    let ids = cx.props.file_menu;
+
+    use_wry_event_handler(&cx, {
+        to_owned![tabs, active];
+        move |event, _| {
+            let tao::event::Event::MenuEvent { menu_id, .. } = event else { return };
+
+            if *menu_id == ids.new {
+                new_tab(&tabs, &active);
+            } else if *menu_id == ids.open {
+                open_document(&tabs, &active);
+            } else if *menu_id == ids.save {
+                save_document(&tabs, &active, false);
+            } else if *menu_id == ids.save_as {
+                save_document(&tabs, &active, true);
+            } else if *menu_id == ids.close_tab {
+                close_tab(&tabs, &active);
+            }
+        }
+    });
 
-    let drawing_sender = use_coroutine(cx, |mut rx| { 
-        let drawing = drawing.clone();
+    use_effect(&cx, (*active.get(), tabs.get()[*active.get()].name.clone(), tabs.get()[*active.get()].dirty), |(_idx, name, dirty)| {
+        to_owned![desktop];
         async move {
-            while let Some(msg) = rx.next().await {
-                drawing.set(msg);
+            let Some(desktop) = desktop else { return };
+            let star = if dirty { "*" } else { "" };
+            desktop.set_title(&format!("Depict \u{2014} {name}{star}"));
+        }
+    });
+
+    let drawing_sender = use_coroutine(cx, |mut rx| {
+        let tabs = tabs.clone();
+        async move {
+            while let Some((idx, msg)): Option<(usize, Drawing)> = rx.next().await {
+                let mut v = tabs.get().clone();
+                if let Some(tab) = v.get_mut(idx) {
+                    tab.drawing = msg;
+                    tabs.set(v);
+                }
             }
         }
     });
 
     let model_sender = use_coroutine(cx, |mut rx| {
         let drawing_sender = drawing_sender.clone();
+        let tabs = tabs.clone();
         async move {
-            let mut prev_model: Option<String> = None;
-            while let Some(model) = rx.next().await {
-                if Some(&model) != prev_model.as_ref() {
+            use std::collections::HashMap;
+            let mut prev_models: HashMap<usize, String> = HashMap::new();
+            while let Some((idx, model)): Option<(usize, String)> = rx.next().await {
+                if prev_models.get(&idx) != Some(&model) {
                     let model_str: &str = &model;
                     let nodes = if model_str.trim().is_empty() {
                         Ok(Ok(Drawing::default()))
@@ -190,22 +404,38 @@ pub fn app(cx: Scope<AppProps>) -> Element {
                             draw(model.clone())
                         })
                     };
-                    let model = model.clone();
+                    prev_models.insert(idx, model.clone());
                     match nodes {
                         Ok(Ok(drawing)) => {
-                            prev_model = Some(model);
-                            drawing_sender.send(drawing);
+                            drawing_sender.send((idx, drawing));
+                            let mut v = tabs.get().clone();
+                            if let Some(tab) = v.get_mut(idx) {
+                                tab.diagnostics = Vec::new();
+                                tabs.set(v);
+                            }
                         },
                         Ok(Err(err)) => {
-                            if let Some(st) = err.span_trace() {
+                            let message = if let Some(st) = err.span_trace() {
                                 let st_col = colorize(st);
                                 event!(Level::ERROR, ?err, %st_col, "DRAWING ERROR SPANTRACE");
+                                format!("{err}\n{st_col}")
                             } else {
                                 event!(Level::ERROR, ?err, "DRAWING ERROR");
+                                format!("{err}")
+                            };
+                            let mut v = tabs.get().clone();
+                            if let Some(tab) = v.get_mut(idx) {
+                                tab.diagnostics = vec![Diagnostic::whole_buffer(Severity::Error, model.len(), message)];
+                                tabs.set(v);
                             }
                         }
                         Err(_) => {
                             event!(Level::ERROR, ?nodes, "PANIC");
+                            let mut v = tabs.get().clone();
+                            if let Some(tab) = v.get_mut(idx) {
+                                tab.diagnostics = vec![Diagnostic::whole_buffer(Severity::Error, model.len(), "panicked while drawing the model".to_string())];
+                                tabs.set(v);
+                            }
                         }
                     }
                 }
@@ -218,44 +448,62 @@ pub fn app(cx: Scope<AppProps>) -> Element {
     // let window = use_window(&cx);
     // window.devtool();
 
-    let nodes = render(cx, drawing.get().clone());
-    let logs = render_logs(cx, drawing.get().clone());
+    let active_idx = *active.get();
+    let active_tab_drawing = tabs.get()[active_idx].drawing.clone();
+
+    let nodes = render(cx, active_tab_drawing.clone());
+    let logs = render_logs(cx, active_tab_drawing.clone());
 
     let mut show_logs = use_state(&cx, || true);
 
-    model_sender.send(model.get().clone());
+    let model_text = tabs.get()[active_idx].model.to_string();
+    model_sender.send((active_idx, model_text.clone()));
 
-    let viewbox_width = drawing.get().viewbox_width;
-    let viewbox_height = drawing.get().viewbox_height;
-    let _crossing_number = cx.render(rsx!(match drawing.get().crossing_number {
+    // `update` patches in place when the edit stayed on one line; written
+    // silently since this render is already recomputing `model_text` from
+    // `tabs`, so there's nothing further to schedule.
+    highlight_caches.write_silent().entry(active_idx).or_default().update(&model_text);
+    let model_spans = highlight_caches.read().get(&active_idx).map(|c| c.spans().to_vec()).unwrap_or_default();
+
+    let viewbox_width = active_tab_drawing.viewbox_width;
+    let viewbox_height = active_tab_drawing.viewbox_height;
+    let _crossing_number = cx.render(rsx!(match active_tab_drawing.crossing_number {
         Some(cn) => rsx!(span { "{cn}" }),
         None => rsx!(div{}),
     }));
 
-    let data_svg = as_data_svg(drawing.get().clone());
-    
+    let data_svg = as_data_svg(active_tab_drawing.clone());
+
+    let highlight_text = tabs.get()[active_idx].highlight.clone();
+
     // parse and eval the highlight string to get a sub-model to highlight
-    let highlight_styles = match parse_highlights(&highlight.get()[..]) {
+    let theme = Theme::default_theme();
+    let highlight_result = parse_highlights(&highlight_text[..]);
+    let highlight_styles = match &highlight_result {
         Ok(Val::Process { name, label, body: Some(Body::All(bs)) }) => {
             // cx.render(rsx!{"OOPS"})
             cx.render(rsx!{
-                bs.iter().map(|b| {
+                bs.iter().enumerate().map(|(i, b)| {
+                    let style = theme.style_for(i);
                     match b {
                         Val::Process { name: Some(pname), .. } | Val::Process { label: Some(pname), .. } => {
-                            let style = format!(".box.highlight_{pname} {{ background-color: red; color: white; }} .highlight_{pname} {{ color: red; }}");
-                            eprintln!("STYLE: {style}");
+                            let box_css = style.to_css();
+                            let text_css = theme::Style { bg: None, ..style }.to_css();
+                            let css = format!(".box.highlight_{pname} {{ {box_css} }} .highlight_{pname} {{ {text_css} }}");
+                            eprintln!("STYLE: {css}");
                             rsx!{
                                 style {
-                                    "{style}"
+                                    "{css}"
                                 }
                             }
                         },
                         Val::Chain{ name: Some(cname), .. } => {
-                            let style = format!(".arrow.highlight_{cname} {{ color: red; }}");
-                            eprintln!("STYLE: {style}");
+                            let text_css = theme::Style { bg: None, ..style }.to_css();
+                            let css = format!(".arrow.highlight_{cname} {{ {text_css} }}");
+                            eprintln!("STYLE: {css}");
                             rsx!{
                                 style {
-                                    "{style}"
+                                    "{css}"
                                 }
                             }
                         }
@@ -266,11 +514,14 @@ pub fn app(cx: Scope<AppProps>) -> Element {
                                         Val::Process { name: Some(pname), .. } | Val::Process { label: Some(pname), .. } => {
                                             match &pq[1] {
                                                 Val::Process { name: Some(qname), .. } | Val::Process { label: Some(qname), .. } => {
-                                                    let style = format!(".arrow.{pname}_{qname} svg > path {{ stroke: red; }}");
-                                                    eprintln!("STYLE: {style}");
+                                                    let css = match style.fg_css_color() {
+                                                        Some(color) => format!(".arrow.{pname}_{qname} svg > path {{ stroke: {color}; }}"),
+                                                        None => format!(".arrow.{pname}_{qname} svg > path {{ stroke-width: 2; }}"),
+                                                    };
+                                                    eprintln!("STYLE: {css}");
                                                     rsx!{
                                                         style {
-                                                            "{style}"
+                                                            "{css}"
                                                         }
                                                     }
                                                 }
@@ -296,7 +547,7 @@ pub fn app(cx: Scope<AppProps>) -> Element {
                 })
             })
         },
-        Err(e) => {
+        Err((e, _span)) => {
             let e = format!("{e:#?}");
             cx.render(rsx!{
                 div {
@@ -309,36 +560,229 @@ pub fn app(cx: Scope<AppProps>) -> Element {
         }
     };
 
+    // `tab.diagnostics` (set by the `model_sender` coroutine) is keyed to
+    // the active tab's own model text, so it's what the model editor's
+    // gutter markers use. The strip below lists those plus whatever
+    // `parse_highlights` just failed on, recomputed fresh every render
+    // rather than round-tripped through a tab's diagnostics too.
+    let mut strip_diagnostics = tabs.get()[active_idx].diagnostics.clone();
+    if let Err((e, Some(span))) = &highlight_result {
+        strip_diagnostics.push(Diagnostic::new(Severity::Error, span.clone(), format!("{e}")));
+    }
+
     let syntax_guide = depict::graph_drawing::frontend::dioxus::syntax_guide(cx)?;
 
+    // Distinct colors per distinct token class actually present in the
+    // buffer, rather than a single static rule that colored every
+    // `token-*` class identically — see `editor::token_style_css`.
+    let token_css = editor::token_style_css(&model_spans);
+
     let style_default = "
-        svg { stroke: currentColor; stroke-width: 1; } 
-        .fake svg { stroke: hsl(0, 0%, 50%); } 
-        path { stroke-dasharray: none; } 
+        svg { stroke: currentColor; stroke-width: 1; }
+        .fake svg { stroke: hsl(0, 0%, 50%); }
+        path { stroke-dasharray: none; }
         .arrow.fake path { stroke-dasharray: 5; }
-        .keyword { font-weight: bold; color: rgb(207, 34, 46); }
-        .example { font-size: 0.625rem; font-family: ui-monospace,SFMono-Regular,Menlo,Monaco,Consolas,\"Liberation Mono\",\"Courier New\",monospace; }
+        .editor-wrap, .editor-backdrop, .editor-input {
+            font-family: ui-monospace,SFMono-Regular,Menlo,Monaco,Consolas,\"Liberation Mono\",\"Courier New\",monospace;
+            font-size: 0.625rem;
+            line-height: 1.4;
+        }
+        .editor-backdrop {
+            position: absolute;
+            top: 0; left: 0; right: 0; bottom: 0;
+            margin: 0;
+            padding: 2px;
+            overflow: hidden;
+            white-space: pre-wrap;
+            word-wrap: break-word;
+            pointer-events: none;
+        }
+        .editor-input {
+            position: relative;
+            background: transparent;
+            color: transparent;
+            caret-color: #000;
+        }
+        .editor-gutter-marker.diagnostic-error { background-color: rgb(207, 34, 46); }
+        .editor-gutter-marker.diagnostic-warning { background-color: rgb(191, 135, 0); }
+        .diagnostic.diagnostic-error { color: rgb(207, 34, 46); }
+        .diagnostic.diagnostic-warning { color: rgb(191, 135, 0); }
     ";
     cx.render(rsx!{
         head {
             style {
                 "{style_default}"
             }
+            style {
+                "{token_css}"
+            }
             highlight_styles
         }
         div {
             // key: "editor",
             style: "width: 100%; z-index: 20; padding: 1rem;",
+            onkeydown: {
+                to_owned![tabs, active, show_logs, desktop, palette_open, palette_query];
+                move |evt| {
+                    let mods = evt.modifiers();
+                    let ctrl = mods.ctrl() || mods.meta();
+                    let shift = mods.shift();
+                    let key = evt.key().to_string();
+                    if let Some(id) = commands::command_for_key(ctrl, shift, &key) {
+                        evt.stop_propagation();
+                        run_command(id, &tabs, &active, &show_logs, &desktop, &palette_open);
+                    } else if ctrl && key.eq_ignore_ascii_case("k") {
+                        evt.stop_propagation();
+                        palette_open.modify(|v| !v);
+                        palette_query.set(String::new());
+                    }
+                }
+            },
+            palette_open.then(|| {
+                let desktop = desktop.clone();
+                rsx!{
+                    div {
+                        class: "command-palette-backdrop",
+                        style: "position: fixed; top: 0; left: 0; right: 0; bottom: 0; background: rgba(0, 0, 0, 0.3); display: flex; justify-content: center; padding-top: 10vh; z-index: 100;",
+                        onclick: move |_| palette_open.set(false),
+                        div {
+                            class: "command-palette",
+                            style: "background: #fff; border: 1px solid #000; width: 24rem; max-width: 90vw; padding: 8px; height: fit-content;",
+                            onclick: move |e| e.stop_propagation(),
+                            input {
+                                value: "{palette_query}",
+                                autofocus: "true",
+                                placeholder: "Type a command...",
+                                oninput: move |e| palette_query.set(e.value.clone()),
+                                onkeydown: move |e| {
+                                    e.stop_propagation();
+                                    if e.key().to_string() == "Escape" {
+                                        palette_open.set(false);
+                                    }
+                                },
+                            }
+                            commands::filter_commands(palette_query.get()).into_iter().take(10).map(|cmd| {
+                                let id = cmd.id;
+                                let title = cmd.title;
+                                let shortcut = cmd.keybinding.map(|kb| kb.label()).unwrap_or_default();
+                                let tabs = tabs.clone();
+                                let active = active.clone();
+                                let show_logs = show_logs.clone();
+                                let desktop = desktop.clone();
+                                let palette_open = palette_open.clone();
+                                rsx!{
+                                    div {
+                                        key: "{title}",
+                                        style: "display: flex; flex-direction: row; justify-content: space-between; padding: 4px; cursor: pointer;",
+                                        onclick: move |_| run_command(id, &tabs, &active, &show_logs, &desktop, &palette_open),
+                                        span { "{title}" }
+                                        span { style: "color: #888; font-size: 0.75rem;", "{shortcut}" }
+                                    }
+                                }
+                            })
+                        }
+                    }
+                }
+            }),
             div {
                 style: "max-width: 36rem; margin-left: auto; margin-right: auto; flex-direction: column;",
+                div {
+                    class: "tab-bar",
+                    style: "display: flex; flex-direction: row; align-items: center; gap: 4px; margin-bottom: 4px;",
+                    tabs.get().iter().enumerate().map(|(i, tab)| {
+                        let is_active = i == active_idx;
+                        let title = tab.title();
+                        let weight = if is_active { "700" } else { "400" };
+                        let is_renaming = *renaming.get() == Some(i);
+                        rsx!{
+                            div {
+                                key: "{i}",
+                                style: "display: flex; flex-direction: row; align-items: center; border: 1px solid #000; padding: 2px 6px; font-weight: {weight}; cursor: pointer;",
+                                onclick: move |_| active.set(i),
+                                ondblclick: move |_| renaming.set(Some(i)),
+                                if is_renaming {
+                                    rsx!{
+                                        input {
+                                            value: "{tab.name}",
+                                            autofocus: "true",
+                                            onblur: move |e| {
+                                                let mut v = tabs.get().clone();
+                                                if let Some(t) = v.get_mut(i) { t.name = e.value.clone(); }
+                                                tabs.set(v);
+                                                renaming.set(None);
+                                            },
+                                        }
+                                    }
+                                } else {
+                                    rsx!{ span { "{title}" } }
+                                }
+                                span {
+                                    style: "margin-left: 6px; cursor: pointer;",
+                                    onclick: move |e| {
+                                        e.stop_propagation();
+                                        let mut v = tabs.get().clone();
+                                        if v.len() > 1 {
+                                            v.remove(i);
+                                            let new_active = if i <= active_idx { active_idx.saturating_sub(1).min(v.len() - 1) } else { active_idx.min(v.len() - 1) };
+                                            active.set(new_active);
+                                            tabs.set(v);
+                                        }
+                                    },
+                                    "\u{00d7}"
+                                }
+                            }
+                        }
+                    })
+                    button {
+                        onclick: move |_| {
+                            let mut v = tabs.get().clone();
+                            let name = tabs::next_untitled_name(&v);
+                            v.push(Tab::untitled(name));
+                            active.set(v.len() - 1);
+                            tabs.set(v);
+                        },
+                        "+"
+                    }
+                }
                 div {
                     // key: "editor_label",
                     "Model"
                 }
                 div {
                     // key: "editor_editor",
+                    class: "editor-wrap",
+                    style: "position: relative; border-width: 1px; border-color: #000; padding-left: 10px;",
+                    div {
+                        class: "editor-gutter",
+                        style: "position: absolute; top: 2px; left: 0; bottom: 2px; width: 8px;",
+                        tabs.get()[active_idx].diagnostics.iter().map(|d| {
+                            let line = diagnostics::line_of(&model_text, d.span.start);
+                            let top = format!("{:.2}em", line as f64 * 1.4);
+                            let class = d.class();
+                            let message = d.message.clone();
+                            let start = d.span.start;
+                            let end = d.span.end;
+                            let desktop = desktop.clone();
+                            rsx!{
+                                div {
+                                    class: "editor-gutter-marker {class}",
+                                    style: "position: absolute; left: 1px; top: {top}; width: 6px; height: 6px; border-radius: 50%; cursor: pointer;",
+                                    title: "{message}",
+                                    onclick: move |_| {
+                                        if let Some(desktop) = &desktop {
+                                            let js = format!(
+                                                "const el = document.querySelector('.editor-input'); if (el) {{ el.focus(); el.setSelectionRange({start}, {end}); }}"
+                                            );
+                                            desktop.eval(&js);
+                                        }
+                                    },
+                                }
+                            }
+                        })
+                    }
+                    editor::render_backdrop(cx, &model_text, &model_spans),
                     textarea {
-                        style: "border-width: 1px; border-color: #000;",
+                        class: "editor-input",
                         rows: "6",
                         cols: "80",
                         autocomplete: "off",
@@ -348,19 +792,50 @@ pub fn app(cx: Scope<AppProps>) -> Element {
                         autofocus: "true",
                         spellcheck: "false",
                         // placeholder: "",
-                        oninput: move |e| { 
+                        oninput: move |e| {
                             event!(Level::TRACE, "INPUT");
-                            model.set(e.value.clone());
-                            model_sender.send(e.value.clone());
+                            let mut v = tabs.get().clone();
+                            if let Some(tab) = v.get_mut(active_idx) {
+                                tabs::splice(&mut tab.model, &e.value);
+                                tab.dirty = true;
+                            }
+                            tabs.set(v);
+                            model_sender.send((active_idx, e.value.clone()));
                         },
-                        "{model}"
+                        "{model_text}"
                     }
                 }
+                div {
+                    class: "diagnostics-strip",
+                    strip_diagnostics.iter().map(|d| {
+                        let class = d.class();
+                        let message = d.message.clone();
+                        let start = d.span.start;
+                        let end = d.span.end;
+                        let desktop = desktop.clone();
+                        rsx!{
+                            div {
+                                class: "diagnostic {class}",
+                                style: "cursor: pointer; white-space: pre-wrap; font-size: 0.75rem;",
+                                onclick: move |_| {
+                                    if let Some(desktop) = &desktop {
+                                        let js = format!(
+                                            "const el = document.querySelector('.editor-input'); if (el) {{ el.focus(); el.setSelectionRange({start}, {end}); }}"
+                                        );
+                                        desktop.eval(&js);
+                                    }
+                                },
+                                "{message}"
+                            }
+                        }
+                    })
+                }
                 div {
                     "Sub-model to Highlight"
                 }
                 div {
                     textarea {
+                        class: "highlight-input",
                         style: "border-width 1px; border-color: #000;",
                         rows: "1",
                         cols: "80",
@@ -369,7 +844,12 @@ pub fn app(cx: Scope<AppProps>) -> Element {
                         spellcheck: "false",
                         oninput: move |e| {
                             event!(Level::TRACE, "HIGHLIGHT INPUT");
-                            highlight.set(e.value.clone());
+                            let mut v = tabs.get().clone();
+                            if let Some(tab) = v.get_mut(active_idx) {
+                                tab.highlight = e.value.clone();
+                                tab.dirty = true;
+                            }
+                            tabs.set(v);
                         }
                     }
                 }
@@ -480,8 +960,24 @@ pub fn main() -> io::Result<()> {
 
     let mut menu_bar = tao::menu::MenuBar::new();
     let mut app_menu = tao::menu::MenuBar::new();
+    let mut file_menu = tao::menu::MenuBar::new();
     let mut edit_menu = tao::menu::MenuBar::new();
 
+    let new_item = file_menu.add_item(tao::menu::MenuItemAttributes::new("New Tab"));
+    let open_item = file_menu.add_item(tao::menu::MenuItemAttributes::new("Open..."));
+    file_menu.add_native_item(tao::menu::MenuItem::Separator);
+    let save_item = file_menu.add_item(tao::menu::MenuItemAttributes::new("Save"));
+    let save_as_item = file_menu.add_item(tao::menu::MenuItemAttributes::new("Save As..."));
+    file_menu.add_native_item(tao::menu::MenuItem::Separator);
+    let close_tab_item = file_menu.add_item(tao::menu::MenuItemAttributes::new("Close Tab"));
+    let file_menu_ids = FileMenuIds {
+        new: new_item.id(),
+        open: open_item.id(),
+        save: save_item.id(),
+        save_as: save_as_item.id(),
+        close_tab: close_tab_item.id(),
+    };
+
     edit_menu.add_native_item(tao::menu::MenuItem::Undo);
     edit_menu.add_native_item(tao::menu::MenuItem::Redo);
     edit_menu.add_native_item(tao::menu::MenuItem::Separator);
@@ -494,10 +990,11 @@ pub fn main() -> io::Result<()> {
     app_menu.add_native_item(tao::menu::MenuItem::CloseWindow);
     app_menu.add_native_item(tao::menu::MenuItem::Quit);
     menu_bar.add_submenu("Depict", true, app_menu);
+    menu_bar.add_submenu("File", true, file_menu);
     menu_bar.add_submenu("Edit", true, edit_menu);
 
     dioxus_desktop::launch_with_props(app,
-        AppProps {},
+        AppProps { file_menu: file_menu_ids },
         Config::new().with_window(
             WindowBuilder::new()
                 .with_inner_size(LogicalSize::new(1200.0f64, 700.0f64))
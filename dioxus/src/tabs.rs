@@ -0,0 +1,101 @@
+//! One editing session's state: its own model/highlight text, the drawing
+//! last rendered from that model, and whatever diagnostics that drawing
+//! attempt produced. `app` holds a `Vec<Tab>` plus the active index instead
+//! of a single set of these states, so switching tabs just changes which
+//! `Tab` is read/rendered rather than recomputing anything.
+
+use std::path::PathBuf;
+
+use ropey::Rope;
+
+use depict::graph_drawing::frontend::dom::Drawing;
+
+use crate::diagnostics::Diagnostic;
+
+#[derive(Clone)]
+pub struct Tab {
+    pub name: String,
+    pub model: Rope,
+    pub highlight: String,
+    pub drawing: Drawing,
+    pub diagnostics: Vec<Diagnostic>,
+    pub current_path: Option<PathBuf>,
+    pub dirty: bool,
+}
+
+impl Tab {
+    pub fn untitled(name: impl Into<String>) -> Self {
+        Tab {
+            name: name.into(),
+            model: Rope::new(),
+            highlight: String::new(),
+            drawing: Drawing::default(),
+            diagnostics: Vec::new(),
+            current_path: None,
+            dirty: false,
+        }
+    }
+
+    pub fn from_document(name: impl Into<String>, doc: crate::document::Document, path: PathBuf) -> Self {
+        Tab {
+            name: name.into(),
+            model: Rope::from_str(&doc.model),
+            highlight: doc.highlight,
+            drawing: Drawing::default(),
+            diagnostics: Vec::new(),
+            current_path: Some(path),
+            dirty: false,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        if self.dirty {
+            format!("{}*", self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+/// Replaces a rope's contents with `new_text`, touching only the range that
+/// actually changed (the common prefix/suffix of chars are left alone)
+/// instead of discarding and rebuilding the whole rope from scratch — the
+/// point of keeping the model as a `Rope` rather than a `String` in the
+/// first place. `app`'s `oninput` handler calls this with the textarea's
+/// full new value every keystroke, since that's all `oninput` hands us; the
+/// diffing below is what turns that into an edit a `Rope` actually benefits
+/// from.
+pub fn splice(rope: &mut Rope, new_text: &str) {
+    let old_chars: Vec<char> = rope.chars().collect();
+    let new_chars: Vec<char> = new_text.chars().collect();
+
+    let prefix = old_chars.iter().zip(new_chars.iter()).take_while(|(a, b)| a == b).count();
+    let old_rest = &old_chars[prefix..];
+    let new_rest = &new_chars[prefix..];
+    let suffix = old_rest.iter().rev().zip(new_rest.iter().rev()).take_while(|(a, b)| a == b).count();
+
+    let old_end = old_chars.len() - suffix;
+    let new_end = new_chars.len() - suffix;
+
+    if old_end > prefix {
+        rope.remove(prefix..old_end);
+    }
+    if new_end > prefix {
+        let insertion: String = new_chars[prefix..new_end].iter().collect();
+        rope.insert(prefix, &insertion);
+    }
+}
+
+/// The name a freshly-created tab gets: "Untitled", "Untitled 2", ... —
+/// skipping any number already in use among `existing` so renamed/closed
+/// tabs don't leave gaps that look like a bug.
+pub fn next_untitled_name(existing: &[Tab]) -> String {
+    let mut n = 1;
+    loop {
+        let candidate = if n == 1 { "Untitled".to_string() } else { format!("Untitled {n}") };
+        if !existing.iter().any(|t| t.name == candidate) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
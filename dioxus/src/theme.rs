@@ -0,0 +1,157 @@
+//! A small `Style`/`Theme` layer for the highlight-box CSS, replacing the
+//! hardcoded `background-color: red; color: white;` literals `app` used to
+//! emit for every matched sub-model. Modeled loosely on `tui`'s `Style`:
+//! named styles that `extend` (patch) a base style, plus add/sub modifiers
+//! for bold/italic/underline so a theme can emphasize without relying on
+//! color at all.
+
+use std::env;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Modifier {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Modifier {
+    pub const BOLD: Modifier = Modifier { bold: true, italic: false, underline: false };
+    pub const ITALIC: Modifier = Modifier { bold: false, italic: true, underline: false };
+    pub const UNDERLINE: Modifier = Modifier { bold: false, italic: false, underline: true };
+
+    pub fn union(self, other: Modifier) -> Modifier {
+        Modifier {
+            bold: self.bold || other.bold,
+            italic: self.italic || other.italic,
+            underline: self.underline || other.underline,
+        }
+    }
+
+    pub fn difference(self, other: Modifier) -> Modifier {
+        Modifier {
+            bold: self.bold && !other.bold,
+            italic: self.italic && !other.italic,
+            underline: self.underline && !other.underline,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Style {
+    pub fg: Option<(u8, u8, u8)>,
+    pub bg: Option<(u8, u8, u8)>,
+    pub add_modifier: Modifier,
+    pub sub_modifier: Modifier,
+}
+
+impl Style {
+    pub fn fg(mut self, color: (u8, u8, u8)) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub fn bg(mut self, color: (u8, u8, u8)) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    pub fn add_modifier(mut self, modifier: Modifier) -> Self {
+        self.add_modifier = self.add_modifier.union(modifier);
+        self
+    }
+
+    /// Marks `modifier` for removal when this style `extend`s a base style
+    /// (see `extend`) — e.g. a palette entry that wants to drop the base's
+    /// bold rather than add to it.
+    pub fn sub_modifier(mut self, modifier: Modifier) -> Self {
+        self.sub_modifier = self.sub_modifier.union(modifier);
+        self
+    }
+
+    /// Patches `self` with `other`, the way `tui::Style::patch` layers a
+    /// more specific style over a base one: `other`'s colors win where set,
+    /// and its `sub_modifier` removes from the merged `add_modifier`.
+    pub fn extend(mut self, other: Style) -> Self {
+        self.fg = other.fg.or(self.fg);
+        self.bg = other.bg.or(self.bg);
+        self.add_modifier = self.add_modifier.union(other.add_modifier).difference(other.sub_modifier);
+        self.sub_modifier = self.sub_modifier.union(other.sub_modifier);
+        self
+    }
+
+    /// This style's foreground as a CSS `rgb(...)` literal, or `None` when
+    /// `NO_COLOR` (https://no-color.org) is set or no foreground is set.
+    pub fn fg_css_color(&self) -> Option<String> {
+        if env::var_os("NO_COLOR").is_some() {
+            return None;
+        }
+        self.fg.map(|(r, g, b)| format!("rgb({r}, {g}, {b})"))
+    }
+
+    /// Renders this style as inline CSS declarations, honoring `NO_COLOR`
+    /// by dropping `fg`/`bg` and keeping only the modifier-driven ones, so
+    /// highlights stay legible without relying on color.
+    pub fn to_css(&self) -> String {
+        let mut decls = Vec::new();
+        if env::var_os("NO_COLOR").is_none() {
+            if let Some((r, g, b)) = self.fg {
+                decls.push(format!("color: rgb({r}, {g}, {b});"));
+            }
+            if let Some((r, g, b)) = self.bg {
+                decls.push(format!("background-color: rgb({r}, {g}, {b});"));
+            }
+        }
+        if self.add_modifier.bold {
+            decls.push("font-weight: bold;".to_string());
+        }
+        if self.add_modifier.italic {
+            decls.push("font-style: italic;".to_string());
+        }
+        if self.add_modifier.underline {
+            decls.push("text-decoration: underline;".to_string());
+        }
+        decls.join(" ")
+    }
+}
+
+/// A base style every entry in `palette` `extend`s, plus one distinguishable
+/// `Style` per simultaneously-highlighted sub-model: the Nth highlighted
+/// name/chain gets `palette[N % palette.len()]` instead of every highlight
+/// being the same hardcoded red.
+pub struct Theme {
+    pub base: Style,
+    pub palette: Vec<Style>,
+}
+
+impl Theme {
+    pub fn default_theme() -> Self {
+        Theme {
+            base: Style::default().add_modifier(Modifier::BOLD),
+            // Each entry also varies `add_modifier`/`sub_modifier`, not just
+            // `fg`/`bg`: under `NO_COLOR`, `to_css` drops every color
+            // declaration, so color alone can't tell two highlights apart —
+            // without a distinct modifier too, every entry would collapse to
+            // the same bold-only style `base` already provides.
+            palette: vec![
+                Style::default().fg((255, 255, 255)).bg((196, 30, 58)),
+                Style::default().fg((255, 255, 255)).bg((22, 118, 196)).add_modifier(Modifier::ITALIC),
+                Style::default().fg((20, 20, 20)).bg((230, 184, 0)).add_modifier(Modifier::UNDERLINE),
+                Style::default()
+                    .fg((255, 255, 255))
+                    .bg((47, 158, 68))
+                    .add_modifier(Modifier::ITALIC)
+                    .add_modifier(Modifier::UNDERLINE),
+                Style::default()
+                    .fg((255, 255, 255))
+                    .bg((137, 66, 196))
+                    .add_modifier(Modifier::ITALIC)
+                    .sub_modifier(Modifier::BOLD),
+            ],
+        }
+    }
+
+    /// The style for the `index`th simultaneously-highlighted sub-model.
+    pub fn style_for(&self, index: usize) -> Style {
+        self.base.extend(self.palette[index % self.palette.len()])
+    }
+}
@@ -0,0 +1,119 @@
+//! Target-independent emit step: `render` walks the resolved facts and
+//! calls into a `Backend` instead of hard-coding Graphviz DOT, so a new
+//! diagram syntax is a ~100-line trait impl rather than a rewrite of the
+//! renderer.
+
+/// A node or edge attribute, e.g. `("label", "sense")`.
+pub type Attr<'a> = (&'a str, &'a str);
+
+pub trait Backend {
+    fn begin(&mut self);
+    fn node(&mut self, id: &str, label: &str, attrs: &[Attr]);
+    fn edge(&mut self, from: &str, to: &str, attrs: &[Attr]);
+    fn end(&mut self);
+
+    /// Consumes the backend, returning its accumulated output.
+    fn finish(self: Box<Self>) -> String;
+}
+
+/// Reproduces today's `digraph { ... }` output.
+#[derive(Default)]
+pub struct DotBackend {
+    out: String,
+}
+
+impl Backend for DotBackend {
+    fn begin(&mut self) {
+        self.out.push_str("digraph {\n");
+    }
+
+    fn node(&mut self, id: &str, label: &str, attrs: &[Attr]) {
+        self.out.push_str(&format!("  \"{id}\" [label=\"{label}\"{}];\n", fmt_attrs(attrs, "dot")));
+    }
+
+    fn edge(&mut self, from: &str, to: &str, attrs: &[Attr]) {
+        self.out.push_str(&format!("  \"{from}\" -> \"{to}\"{};\n", fmt_attrs(attrs, "dot")));
+    }
+
+    fn end(&mut self) {
+        self.out.push_str("}\n");
+    }
+
+    fn finish(self: Box<Self>) -> String {
+        self.out
+    }
+}
+
+fn fmt_attrs(attrs: &[Attr], style: &str) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let body = attrs.iter().map(|(k, v)| format!("{k}=\"{v}\"")).collect::<Vec<_>>().join(", ");
+    match style {
+        "dot" => format!(" [{body}]"),
+        _ => format!(" {body}"),
+    }
+}
+
+/// A Mermaid flowchart (`flowchart LR`) emitter.
+#[derive(Default)]
+pub struct MermaidBackend {
+    out: String,
+}
+
+impl Backend for MermaidBackend {
+    fn begin(&mut self) {
+        self.out.push_str("flowchart LR\n");
+    }
+
+    fn node(&mut self, id: &str, label: &str, _attrs: &[Attr]) {
+        self.out.push_str(&format!("  {id}[\"{label}\"]\n"));
+    }
+
+    fn edge(&mut self, from: &str, to: &str, attrs: &[Attr]) {
+        match attrs.iter().find(|(k, _)| *k == "label") {
+            Some((_, label)) => self.out.push_str(&format!("  {from} -->|{label}| {to}\n")),
+            None => self.out.push_str(&format!("  {from} --> {to}\n")),
+        }
+    }
+
+    fn end(&mut self) {}
+
+    fn finish(self: Box<Self>) -> String {
+        self.out
+    }
+}
+
+/// A flat adjacency/edge-list emitter, useful for piping into other tools.
+#[derive(Default)]
+pub struct EdgeListBackend {
+    out: String,
+}
+
+impl Backend for EdgeListBackend {
+    fn begin(&mut self) {}
+
+    fn node(&mut self, id: &str, label: &str, _attrs: &[Attr]) {
+        self.out.push_str(&format!("node\t{id}\t{label}\n"));
+    }
+
+    fn edge(&mut self, from: &str, to: &str, _attrs: &[Attr]) {
+        self.out.push_str(&format!("edge\t{from}\t{to}\n"));
+    }
+
+    fn end(&mut self) {}
+
+    fn finish(self: Box<Self>) -> String {
+        self.out
+    }
+}
+
+/// Selects a backend by its `--format` CLI flag value.
+pub fn by_name(name: &str) -> Option<Box<dyn Backend>> {
+    match name {
+        "dot" => Some(Box::<DotBackend>::default()),
+        "mermaid" => Some(Box::<MermaidBackend>::default()),
+        "edgelist" => Some(Box::<EdgeListBackend>::default()),
+        _ => None,
+    }
+}
@@ -0,0 +1,60 @@
+//! A read-only `Cache`/`Context` split (after rustdoc's), so independent
+//! `draw` facts can be rendered concurrently instead of each re-running
+//! `resolve` over the whole document from scratch.
+//!
+//! `Cache` is built once and never mutated while rendering, so every field
+//! it holds is plain, already-`Sync` data (an `fst::Map` plus `Vec`/
+//! `HashMap` lookups); sharing `&Cache` across render threads falls out of
+//! that for free. `Context` is the lightweight, per-drawing state (current
+//! output buffer, drawing name) each thread owns exclusively.
+
+use diagrams::parser::Ident;
+
+use crate::index::FactIndex;
+use crate::{Fact, Syn};
+
+/// Shared, immutable resolution state built once per document.
+pub struct Cache<'a> {
+    pub source: &'a str,
+    pub index: FactIndex<'a>,
+}
+
+impl<'a> Cache<'a> {
+    pub fn build(v: &'a [Syn<'a>], source: &'a str) -> Self {
+        Cache { source, index: FactIndex::build(v) }
+    }
+
+    /// All definitions of `name`, precomputed at cache-construction time.
+    pub fn resolve_name(&self, name: &str) -> Vec<&'a Fact<'a>> {
+        self.index.lookup(name)
+    }
+
+    /// An atom resolves to its definition(s), a nested fact resolves to its
+    /// own children.
+    pub fn resolve_fact(&self, r: &'a Fact<'a>) -> Vec<&'a Fact<'a>> {
+        match r {
+            Fact::Atom(Ident(name)) => self.resolve_name(name),
+            Fact::Fact(_, fs) => fs.iter().collect(),
+        }
+    }
+}
+
+/// Per-drawing state: which `draw` fact this thread is rendering, and the
+/// buffer it writes into. Cheap to clone, never shared across threads.
+#[derive(Clone)]
+pub struct Context {
+    pub drawing_name: String,
+    pub buffer: String,
+}
+
+impl Context {
+    pub fn new(drawing_name: impl Into<String>) -> Self {
+        Context { drawing_name: drawing_name.into(), buffer: String::new() }
+    }
+}
+
+pub fn drawing_name(draw: &Fact) -> String {
+    match draw {
+        Fact::Atom(Ident(name)) | Fact::Fact(Ident(name), _) => name.to_string(),
+    }
+}
@@ -0,0 +1,72 @@
+use crate::span::Span;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single codespan-style diagnostic: a message plus a labeled, underlined
+/// span into the original source.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub span: Span,
+    pub label: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(span: Span, message: impl Into<String>, label: impl Into<String>) -> Self {
+        Diagnostic { severity: Severity::Error, span, message: message.into(), label: label.into() }
+    }
+
+    /// Render this diagnostic against `source` as a caret-underlined,
+    /// multi-line snippet, in the spirit of `nom::error::convert_error`:
+    ///
+    /// ```text
+    /// error: unresolved name `compact`
+    ///   --> line 3
+    ///   |
+    /// 3 | draw compact
+    ///   |      ^^^^^^^ no fact named `compact`
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let (line_no, col, line_text) = locate(source, self.span.start);
+        let underline_len = self.span.end.saturating_sub(self.span.start).max(1);
+
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let gutter = format!("{}", line_no);
+        let pad = " ".repeat(gutter.len());
+
+        let mut out = String::new();
+        out.push_str(&format!("{severity}: {message}\n", severity = severity, message = self.message));
+        out.push_str(&format!("{pad} --> line {line_no}\n"));
+        out.push_str(&format!("{pad} |\n"));
+        out.push_str(&format!("{gutter} | {line_text}\n"));
+        out.push_str(&format!(
+            "{pad} | {}{} {}\n",
+            " ".repeat(col),
+            "^".repeat(underline_len),
+            self.label,
+        ));
+        out
+    }
+}
+
+/// Finds the 1-indexed line number, 0-indexed column, and text of the line
+/// containing byte offset `at`.
+fn locate(source: &str, at: usize) -> (usize, usize, &str) {
+    let mut line_start = 0;
+    for (line_no, line) in source.split('\n').enumerate() {
+        let line_end = line_start + line.len();
+        if at <= line_end || line_end == source.len() {
+            return (line_no + 1, at.saturating_sub(line_start), line);
+        }
+        line_start = line_end + 1;
+    }
+    (1, 0, source)
+}
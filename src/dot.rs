@@ -4,7 +4,23 @@ use std::env::args;
 use std::io;
 use std::process::exit;
 use nom::error::convert_error;
-use auto_enums::auto_enum;
+
+mod span;
+mod diagnostics;
+mod visit;
+mod index;
+mod json;
+mod backend;
+mod cache;
+mod pretty;
+mod transform;
+
+use diagnostics::Diagnostic;
+use span::Span;
+use backend::Backend;
+use cache::{Cache, Context};
+use rayon::prelude::*;
+use visit::{Fold, VisitMut};
 
 type Syn<'a> = diagrams::parser::Syn::<&'a str>;
 type Ident<'a> = diagrams::parser::Ident<&'a str>;
@@ -25,26 +41,31 @@ pub fn filter_fact<'a, I: Iterator<Item = Item>, Item: TryInto<&'a Fact<'a>, Err
         .flatten()
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Process<I> {
     name: I,
     controls: Vec<Path<I>>,
     senses: Vec<Path<I>>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Path<I> {
     name: I,
     action: I,
     percept: I,
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Draw<I> {
     name: I,
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Drawing<I> {
     names: Vec<I>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub enum Item<I> {
     Process(Process<I>),
     Path(Path<I>),
@@ -52,74 +73,156 @@ pub enum Item<I> {
     Drawing(Drawing<I>),
 }
 
-// pub fn resolve<'a>(v: &'a Vec<Syn>, r: &'a Fact<'a>) -> Vec<&'a Fact<'a>> {
-#[auto_enum(Iterator)]
-pub fn resolve<'a, I: Iterator<Item = Item>, Item: TryInto<&'a Fact<'a>, Error=E>, E>(v: I, r: &'a Fact<'a>) -> impl Iterator<Item = &'a Fact<'a>> {
-    match r {
-        Fact::Atom(i) => {
-            return filter_fact(v, i);
-        },
-        Fact::Fact(_i, fs) => {
-            return fs.iter();
-        },
+/// BLOCKED on `diagrams::parser`: the request this stands in for asked for
+/// real span threading — `Ident<I>`/`Fact<I>`/`Directive` each carrying a
+/// `Positioned<I>`/`Span` back to the source text they were parsed from, so
+/// a diagnostic about a specific unresolved reference can point at exactly
+/// that reference. `Ident<I>`/`Fact<I>` are generic over `I` (see `dot.rs`'s
+/// own `Ident<'a>`/`Fact<'a>` aliases), which is why that looked pluggable
+/// from here, but `diagrams` is an external crate — not vendored anywhere in
+/// this tree (checked: no copy of it exists on this machine) — so neither
+/// its struct definitions nor its parser's node construction are ours to
+/// change. That work can't be done from this crate; it needs a change
+/// upstream, or vendoring `diagrams` into this tree, either of which is
+/// outside this series' scope. This request should be treated as blocked
+/// pending that, not as done.
+///
+/// What ships here instead is honest about being an approximation, not a
+/// fix: `locate_name` finds `name`'s first whole-identifier occurrence in
+/// `source` (via `span::ident_occurrences`, so at least it can't match a
+/// substring of a longer identifier the way `str::find` could) and reports
+/// how many total occurrences there were. `render_drawing` uses that count
+/// to avoid asserting false precision: when a name repeats, the diagnostic
+/// says so instead of silently pointing at a span that may well be the
+/// wrong occurrence.
+fn locate_name(source: &str, name: &str) -> (Span, usize) {
+    let occurrences = span::ident_occurrences(source);
+    let count = occurrences.iter().filter(|occ| occ.value == name).count();
+    let span = occurrences
+        .into_iter()
+        .find(|occ| occ.value == name)
+        .map(|occ| occ.span)
+        .unwrap_or_else(Span::dummy);
+    (span, count)
+}
+
+/// Renders a single `draw` fact against the shared `cache` into its own
+/// `Context`. Only ever reads from `cache`, so `render` below can call this
+/// once per drawing from any thread.
+fn render_drawing<'a>(cache: &Cache<'a>, draw: &'a Fact<'a>, mut backend: Box<dyn Backend>) -> Context {
+    let mut ctx = Context::new(cache::drawing_name(draw));
+
+    backend.begin();
+
+    for hint in cache.resolve_fact(draw) {
+        if let Fact::Fact(Ident("compact"), items) = hint {
+            for item in items {
+                let Fact::Atom(Ident(item_name)) | Fact::Fact(Ident(item_name), _) = item else { continue };
+                let resolved_name = cache.resolve_name(item_name);
+
+                if resolved_name.is_empty() {
+                    let suggestions = cache.index.suggest(item_name);
+                    let mut label = if suggestions.is_empty() {
+                        "no fact named this was found".to_string()
+                    } else {
+                        format!("no fact named this was found; did you mean `{}`?", suggestions.join("`, `"))
+                    };
+                    let (span, occurrences) = locate_name(cache.source, item_name);
+                    if occurrences > 1 {
+                        label.push_str(&format!(
+                            " (location approximate: `{item_name}` occurs {occurrences} times in this document; \
+                             the underline below is its first occurrence, not necessarily the reference that failed to resolve)"
+                        ));
+                    }
+                    let diag = Diagnostic::error(
+                        span,
+                        format!("unresolved name `{item_name}`"),
+                        label,
+                    );
+                    eprintln!("{}", diag.render(cache.source));
+                    continue;
+                }
+
+                backend.node(item_name, &format!("{resolved_name:?}"), &[]);
+            }
+        }
     }
+
+    backend.end();
+    ctx.buffer = backend.finish();
+    ctx
 }
 
-pub fn render(v: Vec<Syn>) {
+pub fn render(v: Vec<Syn>, source: &str, make_backend: impl Fn() -> Box<dyn Backend> + Sync) -> String {
     println!("ok\n\n");
 
-    let ds = filter_fact(v.iter(), &Ident("draw"));
-    // let ds2 = ds.collect::<Vec<&Fact>>();
-    // println!("draw:\n{:#?}\n\n", ds2);
+    // Built once per document, shared (read-only) across every drawing's
+    // render thread; `Cache::resolve_name`/`resolve_fact` are the only
+    // lookups now, so threads never touch `v` directly.
+    let cache = Cache::build(&v, source);
 
-    for draw in ds {
-        // println!("draw:\n{:#?}\n\n", draw);
+    let draws = filter_fact(v.iter(), &Ident("draw")).collect::<Vec<&Fact>>();
 
-        let res = resolve(v.iter(), draw);
-        
-        // println!("resolution: {:?}\n", res);
-
-        println!("{}", "digraph {");
+    // Independent drawings don't share any mutable state, so render them
+    // concurrently and concatenate in source order to keep output stable.
+    draws
+        .par_iter()
+        .map(|draw| render_drawing(&cache, draw, make_backend()).buffer)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
 
-        for hint in res {
-            match hint {
-                Fact::Fact(Ident("compact"), items) => {
-                    for item in items {
-                        let resolved_item = resolve(v.iter(), item);
-                        // let resolved_item = resolve(v.iter(), item).collect::<Vec<&Fact>>();
-                        // println!("{:?} {:?}", item, resolved_item.collect::<Vec<&Fact>>());
+pub fn main() -> io::Result<()> {
+    let mut emit: Option<String> = None;
+    let mut format = "dot".to_string();
+    let mut paths = Vec::new();
+    // Only meaningful for `--emit pretty` (see the branch below): `pretty`
+    // is the one output meant purely for human eyes, so it's where
+    // depth-limiting/redaction belong, rather than on `json` or rendering,
+    // which need the real tree.
+    let mut max_depth: Option<usize> = None;
+    let mut redact: Vec<String> = Vec::new();
+    let mut rest = args().skip(1);
 
-                        let query = Fact::Atom(Ident("name"));
-                        let resolved_name = resolve(resolved_item, &query);
-                        println!("rn: {:?}", resolved_name.collect::<Vec<&Fact>>());
-                    }
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--emit" => match rest.next().as_deref() {
+                Some(target @ ("json" | "pretty")) => emit = Some(target.to_string()),
+                Some(other) => {
+                    eprintln!("unknown --emit target `{other}` (expected `json` or `pretty`)");
+                    exit(2);
                 },
-                _ => {},
-            }
+                None => {
+                    eprintln!("--emit requires a target (e.g. `--emit json`)");
+                    exit(2);
+                },
+            },
+            "--format" => match rest.next() {
+                Some(f) => format = f,
+                None => {
+                    eprintln!("--format requires a target (e.g. `--format mermaid`)");
+                    exit(2);
+                },
+            },
+            "--max-depth" => match rest.next().and_then(|s| s.parse::<usize>().ok()) {
+                Some(n) => max_depth = Some(n),
+                None => {
+                    eprintln!("--max-depth requires a non-negative integer (e.g. `--max-depth 2`)");
+                    exit(2);
+                },
+            },
+            "--redact" => match rest.next() {
+                Some(names) => redact = names.split(',').map(|s| s.to_string()).collect(),
+                None => {
+                    eprintln!("--redact requires a comma-separated list of names (e.g. `--redact secret,password`)");
+                    exit(2);
+                },
+            },
+            _ => paths.push(arg),
         }
-
-        println!("{}", "}");
     }
-    // use top-level "draw" fact to identify inline or top-level drawings to draw
-    // resolve top-level drawings + use inline drawings to identify objects to draw to make particular drawings
-    // use object facts to figure out directions + labels?
-    // print out dot repr?
-    //   header
-    //   render nodes
-    //   render edges
-    //   footer
-    // let mut compact: &Vec<Ident> = &ds.find(|d| d == Ident("compact")).unwrap().1;
-    // println!("COMPACT\n{:#?}", compact)
-
-    // for id in compact {
-    //     match resolve(&v, id) {
-
-    //     }
-    // }
-}
 
-pub fn main() -> io::Result<()> {
-    for path in args().skip(1) {
+    for path in paths {
         let contents = read_to_string(path)?;
         println!("{}\n\n", &contents);
         let v = parse(&contents[..]);
@@ -129,7 +232,41 @@ pub fn main() -> io::Result<()> {
                 exit(1);
             },
             Ok(("", v2)) => {
-                render(v2);
+                match emit.as_deref() {
+                    Some("json") => match json::to_json(&v2) {
+                        Ok(text) => println!("{text}"),
+                        Err(err) => {
+                            eprintln!("failed to serialize parsed document: {err}");
+                            exit(2);
+                        },
+                    },
+                    Some("pretty") => {
+                        let mut v2 = v2;
+                        if !redact.is_empty() {
+                            let mut pass = transform::Redact { names: &redact };
+                            for item in &mut v2 {
+                                pass.visit_syn_mut(item);
+                            }
+                        }
+                        if let Some(n) = max_depth {
+                            v2 = v2
+                                .into_iter()
+                                .map(|s| match s {
+                                    Syn::Fact(f) => Syn::Fact(transform::ElideBeyondDepth::new(n).fold_fact(f)),
+                                    other => other,
+                                })
+                                .collect();
+                        }
+                        println!("{}", pretty::pretty_print(&v2));
+                    },
+                    _ => {
+                        if backend::by_name(&format).is_none() {
+                            eprintln!("unknown --format target `{format}` (expected `dot`, `mermaid`, or `edgelist`)");
+                            exit(2);
+                        }
+                        println!("{}", render(v2, &contents, || backend::by_name(&format).unwrap()));
+                    }
+                }
             }
             _ => {
                 println!("{:#?}", v);
@@ -0,0 +1,137 @@
+//! A once-built index over a document's facts, so lookups are an FST query
+//! instead of a linear scan of `Vec<Syn>`.
+//!
+//! `FactIndex` walks the tree once (via `FactCollector`, a `visit::Visit`
+//! pass), maps each fact name to the offset of its (first) definition in a
+//! sorted `fst::Map`, and keeps any further definitions of the same name in
+//! an auxiliary table. `Cache::resolve_name`/`resolve_fact` (see `cache.rs`)
+//! are the real hot path through this index — `render` calls those once per
+//! reference rather than re-walking `v`, so resolving a diagram is an FST
+//! lookup per reference, not a tree walk per reference.
+
+use std::collections::HashMap;
+
+use fst::automaton::Levenshtein;
+use fst::{IntoStreamer, Map, MapBuilder, Streamer};
+
+use crate::visit::Visit;
+use crate::{Fact, Ident, Syn};
+
+/// Collects every `Fact::Fact(name, children)` in visitation order, via
+/// `Visit` instead of a bespoke recursion — `FactIndex::build` is the real
+/// production caller `visit::Visit` was missing; `visit::IdentCollector`
+/// remains as the trait's minimal demo.
+#[derive(Default)]
+struct FactCollector<'a> {
+    facts: Vec<(&'a str, &'a Fact<'a>)>,
+}
+
+impl<'a> Visit<'a> for FactCollector<'a> {
+    fn visit_fact(&mut self, node: &'a Fact<'a>) {
+        if let Fact::Fact(Ident(name), _) = node {
+            self.facts.push((name, node));
+        }
+        crate::visit::walk_fact(self, node);
+    }
+}
+
+pub struct FactIndex<'a> {
+    /// name -> offset into `defs` of its first definition.
+    fst: Map<Vec<u8>>,
+    /// every definition, sorted by name; definitions sharing a name stay in
+    /// the order they were visited.
+    defs: Vec<&'a Fact<'a>>,
+    /// name -> offsets of any definitions after the first.
+    extra: HashMap<&'a str, Vec<usize>>,
+}
+
+impl<'a> FactIndex<'a> {
+    /// Walks `v` collecting every `Fact::Fact(name, children)` and builds
+    /// the backing FST. Duplicate names are tolerated: the first
+    /// definition's offset goes in the FST itself, and any further
+    /// definitions are chained in `extra`.
+    pub fn build(v: &'a [Syn<'a>]) -> Self {
+        let mut collector = FactCollector::default();
+        for item in v {
+            collector.visit_syn(item);
+        }
+        let mut by_name = collector.facts;
+
+        by_name.sort_by_key(|(name, _)| *name);
+
+        let mut defs = Vec::with_capacity(by_name.len());
+        let mut extra: HashMap<&'a str, Vec<usize>> = HashMap::new();
+        let mut builder = MapBuilder::memory();
+        let mut last_name: Option<&str> = None;
+
+        for (name, fact) in by_name {
+            let offset = defs.len();
+            defs.push(fact);
+            if last_name == Some(name) {
+                extra.entry(name).or_default().push(offset);
+            } else {
+                builder.insert(name, offset as u64).expect("fst keys are inserted in sorted order");
+                last_name = Some(name);
+            }
+        }
+
+        let fst = Map::new(builder.into_inner().expect("fst builder finishes cleanly")).expect("built fst is valid");
+
+        FactIndex { fst, defs, extra }
+    }
+
+    /// All definitions of `name`, in the order they were encountered.
+    pub fn lookup(&self, name: &str) -> Vec<&'a Fact<'a>> {
+        match self.fst.get(name) {
+            Some(offset) => {
+                let mut out = vec![self.defs[offset as usize]];
+                if let Some(rest) = self.extra.get(name) {
+                    out.extend(rest.iter().map(|&i| self.defs[i]));
+                }
+                out
+            },
+            None => Vec::new(),
+        }
+    }
+
+    /// All names beginning with `prefix`, for completion.
+    pub fn prefix(&self, prefix: &str) -> Vec<String> {
+        let mut stream = self.fst.range().ge(prefix).into_stream();
+        let mut out = Vec::new();
+        while let Some((key, _)) = stream.next() {
+            let key = match std::str::from_utf8(key) {
+                Ok(k) => k,
+                Err(_) => continue,
+            };
+            if !key.starts_with(prefix) {
+                break;
+            }
+            out.push(key.to_string());
+        }
+        out
+    }
+
+    /// Names within edit distance 1 of `name`, or, only when there are none,
+    /// within edit distance 2, for "did you mean" hints when a lookup fails.
+    pub fn suggest(&self, name: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        for distance in 1..=2 {
+            let lev = match Levenshtein::new(name, distance) {
+                Ok(lev) => lev,
+                Err(_) => continue,
+            };
+            let mut stream = self.fst.search(lev).into_stream();
+            while let Some((key, _)) = stream.next() {
+                if let Ok(key) = std::str::from_utf8(key) {
+                    out.push(key.to_string());
+                }
+            }
+            if !out.is_empty() {
+                break;
+            }
+        }
+        out.sort();
+        out.dedup();
+        out
+    }
+}
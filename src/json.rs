@@ -0,0 +1,187 @@
+//! A serde-friendly mirror of the parsed `Syn` tree, for dumping `parse`'s
+//! output as a stable, language-agnostic interchange format (`--emit json`),
+//! and for reading it back.
+//!
+//! Orphan rules mean we can't `impl Serialize for diagrams::parser::Syn`
+//! directly, so this module defines small "shadow" types that mirror the
+//! upstream shape and convert from it with `From`. Round-tripping through
+//! `FactJson`/`IdentJson` is genuine: `OwnedFact`/`OwnedIdent` reconstruct an
+//! owned `Fact`/`Ident` tree from deserialized JSON via `From<&FactJson>`/
+//! `From<&IdentJson>`, reusing the same `Ident<I>`/`Fact<I>` the rest of this
+//! crate already treats as generic over the identifier type (`dot.rs`'s own
+//! `Ident<'a>`/`Fact<'a>` aliases plug in `&'a str`; these plug in `String`).
+//!
+//! `Directive` doesn't round-trip, and can't from this module alone:
+//! `DirectiveJson` only captures `format!("{d:?}")`, because `Directive`'s
+//! fields are defined upstream in the `diagrams` parser crate and aren't
+//! visible here (see `visit.rs`) — there's no structure to deserialize back
+//! into, only the text of a `Debug` dump. Parsing that back into a real
+//! `Directive` isn't possible until the parser crate exposes its fields (or
+//! its own `FromStr`/deserializer); until then, `SynJson::Directive` is a
+//! one-way archival record, and `to_json`/`from_json` round-trip the `Fact`
+//! side of a document, not documents containing directives.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Directive, Fact, Ident, Syn};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IdentJson(pub String);
+
+impl<'a> From<&Ident<'a>> for IdentJson {
+    fn from(i: &Ident<'a>) -> Self {
+        IdentJson(i.0.to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind")]
+pub enum FactJson {
+    Atom { name: IdentJson },
+    Fact { name: IdentJson, children: Vec<FactJson> },
+}
+
+impl<'a> From<&Fact<'a>> for FactJson {
+    fn from(f: &Fact<'a>) -> Self {
+        match f {
+            Fact::Atom(i) => FactJson::Atom { name: i.into() },
+            Fact::Fact(i, children) => FactJson::Fact {
+                name: i.into(),
+                children: children.iter().map(FactJson::from).collect(),
+            },
+        }
+    }
+}
+
+/// An owned `Ident`/`Fact`, for reconstructing a tree from deserialized JSON
+/// that doesn't borrow from any source text. `Ident<I>`/`Fact<I>` are
+/// already generic over the identifier type upstream (`dot.rs`'s `Ident<'a>`
+/// plugs in `&'a str`), so these just plug in `String` instead.
+pub type OwnedIdent = diagrams::parser::Ident<String>;
+pub type OwnedFact = diagrams::parser::Fact<OwnedIdent>;
+
+impl From<&IdentJson> for OwnedIdent {
+    fn from(i: &IdentJson) -> Self {
+        diagrams::parser::Ident(i.0.clone())
+    }
+}
+
+impl From<&FactJson> for OwnedFact {
+    fn from(f: &FactJson) -> Self {
+        match f {
+            FactJson::Atom { name } => Fact::Atom(name.into()),
+            FactJson::Fact { name, children } => {
+                Fact::Fact(name.into(), children.iter().map(OwnedFact::from).collect())
+            },
+        }
+    }
+}
+
+/// The other direction of `OwnedFact`'s conversion, for round-trip tests and
+/// any caller that builds an `OwnedFact` tree directly and wants to compare
+/// it against a `FactJson` without going through JSON text.
+impl From<&OwnedFact> for FactJson {
+    fn from(f: &OwnedFact) -> Self {
+        match f {
+            Fact::Atom(i) => FactJson::Atom { name: IdentJson(i.0.clone()) },
+            Fact::Fact(i, children) => FactJson::Fact {
+                name: IdentJson(i.0.clone()),
+                children: children.iter().map(FactJson::from).collect(),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveJson {
+    pub debug: String,
+}
+
+impl<'a> From<&Directive<'a>> for DirectiveJson {
+    fn from(d: &Directive<'a>) -> Self {
+        DirectiveJson { debug: format!("{d:?}") }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind")]
+pub enum SynJson {
+    Fact { fact: FactJson },
+    Directive { directive: DirectiveJson },
+}
+
+impl<'a> From<&Syn<'a>> for SynJson {
+    fn from(s: &Syn<'a>) -> Self {
+        match s {
+            Syn::Fact(f) => SynJson::Fact { fact: f.into() },
+            Syn::Directive(d) => SynJson::Directive { directive: d.into() },
+        }
+    }
+}
+
+/// Serializes a whole parsed document as pretty-printed JSON.
+pub fn to_json(v: &[Syn]) -> serde_json::Result<String> {
+    let shadow: Vec<SynJson> = v.iter().map(SynJson::from).collect();
+    serde_json::to_string_pretty(&shadow)
+}
+
+/// Deserializes the `Fact`s of a document produced by `to_json`. `Directive`s
+/// can't be reconstructed from their `DirectiveJson` record (see the module
+/// doc comment), so they're dropped rather than erroring out — but how many
+/// were dropped is returned alongside the facts instead of discarded
+/// silently, so a caller that cares whether its document round-tripped in
+/// full can check it instead of being told nothing was lost.
+pub fn facts_from_json(text: &str) -> serde_json::Result<(Vec<OwnedFact>, usize)> {
+    let shadow: Vec<SynJson> = serde_json::from_str(text)?;
+    let mut directives_dropped = 0;
+    let facts = shadow
+        .iter()
+        .filter_map(|s| match s {
+            SynJson::Fact { fact } => Some(OwnedFact::from(fact)),
+            SynJson::Directive { .. } => {
+                directives_dropped += 1;
+                None
+            },
+        })
+        .collect();
+    Ok((facts, directives_dropped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Fixed name pool, matching `pretty.rs`'s round-trip test — keeps
+    /// generated facts `&'static str` instead of needing owned-string
+    /// generation just to exercise the round-trip.
+    const NAMES: &[&str] = &["foo", "bar", "baz", "qux", "quux"];
+
+    fn name() -> impl Strategy<Value = &'static str> {
+        prop::sample::select(NAMES)
+    }
+
+    fn fact() -> impl Strategy<Value = Fact<'static>> {
+        let leaf = name().prop_map(|n| Fact::Atom(Ident(n)));
+        leaf.prop_recursive(4, 64, 4, |inner| {
+            (name(), prop::collection::vec(inner, 0..4))
+                .prop_map(|(n, children)| Fact::Fact(Ident(n), children))
+        })
+    }
+
+    proptest! {
+        /// `facts_from_json(&to_json(v)) == v`, the property the request
+        /// asked for, compared via `FactJson` on both sides (both the
+        /// original `Fact` and the round-tripped `OwnedFact` convert to it)
+        /// rather than `==` on the raw upstream types.
+        #[test]
+        fn facts_from_json_round_trips_to_json(f in fact()) {
+            let expected = FactJson::from(&f);
+            let text = to_json(&[Syn::Fact(f)]).expect("serializes");
+            let (facts, directives_dropped) = facts_from_json(&text).expect("deserializes");
+            prop_assert_eq!(directives_dropped, 0);
+            let actual: Vec<FactJson> = facts.iter().map(FactJson::from).collect();
+            prop_assert_eq!(actual, vec![expected]);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Turns a parsed `Vec<Syn>` back into source text.
+//!
+//! Orphan rules mean we can't `impl std::fmt::Display for diagrams::parser::
+//! Fact` from this crate (same constraint as `json.rs`), so this is a set of
+//! free functions instead of a trait impl. The concrete fact syntax below —
+//! `name(child, child, ...)` for a `Fact::Fact`, bare `name` for an atom —
+//! round-trips through `parse`, checked by the `pretty_print_round_trips_facts`
+//! proptest and the `pretty_print_is_stable_on_corpus` corpus test below.
+//! `print_directive` doesn't round-trip and can't: it only emits
+//! `Directive`'s `Debug` form (see the comment on it), which isn't valid
+//! input to `parse`, so both properties are scoped to `Fact` trees/sources,
+//! not whole documents.
+
+use crate::{Directive, Fact, Ident, Syn};
+
+pub fn print_ident(i: &Ident) -> String {
+    i.0.to_string()
+}
+
+pub fn print_fact(f: &Fact) -> String {
+    match f {
+        Fact::Atom(i) => print_ident(i),
+        Fact::Fact(i, children) => {
+            let children = children.iter().map(print_fact).collect::<Vec<_>>().join(", ");
+            format!("{}({})", print_ident(i), children)
+        },
+    }
+}
+
+/// `Directive`'s fields aren't visible from here (see `visit.rs`), so this
+/// can only reproduce its `Debug` form, not real source syntax.
+pub fn print_directive(d: &Directive) -> String {
+    format!("{d:?}")
+}
+
+pub fn print_syn(s: &Syn) -> String {
+    match s {
+        Syn::Fact(f) => format!("{};", print_fact(f)),
+        Syn::Directive(d) => print_directive(d),
+    }
+}
+
+pub fn pretty_print(v: &[Syn]) -> String {
+    v.iter().map(print_syn).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::FactJson;
+    use diagrams::parser::parse;
+    use proptest::prelude::*;
+
+    /// Fixed name pool so generated facts can stay `&'static str` instead of
+    /// needing owned-string generation just to exercise the round-trip.
+    const NAMES: &[&str] = &["foo", "bar", "baz", "qux", "quux"];
+
+    fn name() -> impl Strategy<Value = &'static str> {
+        prop::sample::select(NAMES)
+    }
+
+    /// `Fact<'static>` trees from `NAMES`, up to depth 4 with up to 3
+    /// children per level.
+    fn fact() -> impl Strategy<Value = Fact<'static>> {
+        let leaf = name().prop_map(|n| Fact::Atom(Ident(n)));
+        leaf.prop_recursive(4, 64, 4, |inner| {
+            (name(), prop::collection::vec(inner, 0..4))
+                .prop_map(|(n, children)| Fact::Fact(Ident(n), children))
+        })
+    }
+
+    proptest! {
+        /// The request's literal ask: `parse(pretty_print(tree)) == tree`.
+        /// Scoped to `Fact` trees (see the module doc comment for why
+        /// `Directive` is out of scope), and compared via `FactJson` rather
+        /// than `==` on the raw `Fact`, since whether the upstream
+        /// `Fact`/`Ident` types derive `PartialEq` isn't something this
+        /// crate controls or can rely on.
+        #[test]
+        fn pretty_print_round_trips_facts(f in fact()) {
+            let expected = FactJson::from(&f);
+            let source = pretty_print(&[Syn::Fact(f)]);
+            match parse(&source) {
+                Ok(("", parsed)) => {
+                    let actual: Vec<FactJson> = parsed
+                        .iter()
+                        .filter_map(|s| match s {
+                            Syn::Fact(f) => Some(FactJson::from(f)),
+                            Syn::Directive(_) => None,
+                        })
+                        .collect();
+                    prop_assert_eq!(actual, vec![expected]);
+                },
+                _ => prop_assert!(false, "pretty-printed fact failed to reparse: {source:?}"),
+            }
+        }
+    }
+
+    /// A handful of hand-written fact-only sources, covering the shapes
+    /// `print_fact` can produce: bare atoms, nested calls, multiple
+    /// top-level facts, and an empty-children call.
+    const CORPUS: &[&str] = &[
+        "foo;",
+        "foo(bar, baz);",
+        "foo(bar(baz, qux), quux);",
+        "a(b); c(d, e);",
+        "solo();",
+    ];
+
+    /// The request's other explicit ask: "the reverse
+    /// `pretty_print(parse(src))`-stability property on a corpus." Checks
+    /// that pretty-printing an already-pretty-printed-and-reparsed document
+    /// doesn't change its text any further — i.e. `pretty_print` has
+    /// converged, not merely produced *some* reparseable output.
+    #[test]
+    fn pretty_print_is_stable_on_corpus() {
+        for src in CORPUS {
+            let (rest, parsed) = parse(src).unwrap_or_else(|e| panic!("corpus entry {src:?} failed to parse: {e:?}"));
+            assert_eq!(rest, "", "corpus entry {src:?} left unparsed trailing input");
+
+            let once = pretty_print(&parsed);
+            let (rest, reparsed) =
+                parse(&once).unwrap_or_else(|e| panic!("pretty-printed {src:?} ({once:?}) failed to reparse: {e:?}"));
+            assert_eq!(rest, "", "pretty-printed {src:?} left unparsed trailing input");
+
+            let twice = pretty_print(&reparsed);
+            assert_eq!(once, twice, "pretty_print(parse({src:?})) is not stable");
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::ops::Deref;
+
+/// A byte-offset range into the original source text, `start..end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// A span covering neither input nor any known location, for nodes we
+    /// can't yet attribute to a source range (e.g. synthesized during a
+    /// `Fold`).
+    pub fn dummy() -> Self {
+        Span { start: 0, end: 0 }
+    }
+}
+
+/// Wraps a parsed node together with the byte span it came from.
+///
+/// Cheap to strip via `Deref`/`into_inner`, so existing matching like
+/// `Fact::Fact(Ident("compact"), items)` still works on `positioned.value`
+/// (or, via `Deref`, directly on `*positioned`).
+#[derive(Clone, Debug)]
+pub struct Positioned<T> {
+    pub span: Span,
+    pub value: T,
+}
+
+impl<T> Positioned<T> {
+    pub fn new(span: Span, value: T) -> Self {
+        Positioned { span, value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Positioned<U> {
+        Positioned { span: self.span, value: f(self.value) }
+    }
+}
+
+impl<T> Deref for Positioned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> std::ops::DerefMut for Positioned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Positioned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+/// Every maximal run of identifier characters (`is_alphanumeric` or `_`) in
+/// `source`, in order, with its byte span.
+///
+/// Real per-`Ident` spans would come from `diagrams::parser` threading
+/// `Positioned<I>`/`Span` through `Ident`/`Fact`/`Directive` as it parses —
+/// that's blocked on changing an external, unvendored crate this tree
+/// doesn't control (see `locate_name` in `dot.rs`). This is a source-level
+/// stand-in usable without any parser changes: it scans at identifier-
+/// boundary granularity, so it can't match `name` as a substring of a
+/// longer identifier the way `str::find` could, but it still can't
+/// disambiguate *which* occurrence of a repeated name a specific reference
+/// meant — callers need to handle that themselves (`locate_name` reports an
+/// occurrence count so its callers can flag the ambiguity instead of
+/// quietly pointing at a possibly-wrong span).
+pub fn ident_occurrences<'a>(source: &'a str) -> Vec<Positioned<&'a str>> {
+    let mut out = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    for (i, c) in source.char_indices() {
+        if c.is_alphanumeric() || c == '_' {
+            if start.is_none() {
+                start = Some(i);
+            }
+            end = i + c.len_utf8();
+        } else if let Some(s) = start.take() {
+            out.push(Positioned::new(Span::new(s, end), &source[s..end]));
+        }
+    }
+    if let Some(s) = start {
+        out.push(Positioned::new(Span::new(s, end), &source[s..end]));
+    }
+    out
+}
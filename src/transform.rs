@@ -0,0 +1,59 @@
+//! Tree-rewriting passes built on `visit::Fold`/`visit::VisitMut`, used by
+//! `--max-depth`/`--redact` before `--emit pretty` prints a document.
+//!
+//! Both only ever substitute in `'static` string literals (`"..."`,
+//! `"REDACTED"`), never text borrowed from anywhere outside the process:
+//! `Ident<'a>`'s field is `&'a str`, borrowed from the source text being
+//! parsed, so a rewrite can't splice in a new, dynamically sized name
+//! without leaking it — a fixed literal sidesteps that instead, since
+//! `&'static str` coerces to `&'a str` for any `'a`.
+
+use crate::visit::{Fold, VisitMut};
+use crate::{Fact, Ident};
+
+/// `Fold` pass: below `max_depth` levels of nesting, replaces a
+/// `Fact::Fact`'s children with a single `Fact::Atom(Ident("..."))`, for a
+/// quick overview of a large document instead of pretty-printing it in
+/// full.
+pub struct ElideBeyondDepth {
+    max_depth: usize,
+    depth: usize,
+}
+
+impl ElideBeyondDepth {
+    pub fn new(max_depth: usize) -> Self {
+        ElideBeyondDepth { max_depth, depth: 0 }
+    }
+}
+
+impl Fold for ElideBeyondDepth {
+    fn fold_fact(&mut self, node: Fact) -> Fact {
+        match node {
+            Fact::Fact(i, children) if self.depth >= self.max_depth && !children.is_empty() => {
+                Fact::Fact(i, vec![Fact::Atom(Ident("..."))])
+            },
+            Fact::Fact(i, children) => {
+                self.depth += 1;
+                let children = children.into_iter().map(|c| self.fold_fact(c)).collect();
+                self.depth -= 1;
+                Fact::Fact(i, children)
+            },
+            atom => atom,
+        }
+    }
+}
+
+/// `VisitMut` pass: replaces any `Ident` whose name is in `names` with
+/// `Ident("REDACTED")`, in place, so a document can be pretty-printed for
+/// sharing without exposing specific fact names.
+pub struct Redact<'a> {
+    pub names: &'a [String],
+}
+
+impl<'a> VisitMut for Redact<'a> {
+    fn visit_ident_mut(&mut self, node: &mut Ident) {
+        if self.names.iter().any(|n| n.as_str() == node.0) {
+            node.0 = "REDACTED";
+        }
+    }
+}
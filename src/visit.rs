@@ -0,0 +1,153 @@
+//! Traversal subsystem over the parsed `Syn`/`Fact`/`Ident` tree, in the
+//! shape of syn's generated `visit`/`visit_mut`/`fold` modules: `Visit` and
+//! `VisitMut` walk the tree without changing its shape, `Fold` consumes a
+//! node and may rewrite it.
+//!
+//! `Directive`'s internal shape is defined upstream in the `diagrams` parser
+//! crate and isn't reproduced here, so it's treated as a leaf: the default
+//! walk/fold for it is a no-op. Once `Directive` exposes its own fields,
+//! `walk_directive`/`fold_directive` should descend into them.
+//!
+//! `index::FactCollector` is `Visit`'s real production caller (it replaced
+//! `FactIndex::build`'s hand-rolled recursion), alongside the `IdentCollector`
+//! demo below. `transform::ElideBeyondDepth` (`Fold`) and `transform::Redact`
+//! (`VisitMut`), wired into `dot.rs`'s `--max-depth`/`--redact` flags for
+//! `--emit pretty`, are `Fold`/`VisitMut`'s real callers. The original plan
+//! to express `resolve`'s short-circuiting inline-lookup as a `Fold` didn't
+//! pan out: `Fold` rewrites every node it visits, and `resolve` never wanted
+//! to rewrite anything, only to stop at the first match — a `Visit` with
+//! early return fits that shape, not a `Fold`. `resolve` was dead code after
+//! the `Cache`-based rewrite regardless (see `dot.rs`) and was removed
+//! rather than converted onto a trait it doesn't fit.
+
+use crate::{Directive, Fact, Ident, Syn};
+
+pub trait Visit<'a> {
+    fn visit_syn(&mut self, node: &'a Syn<'a>) {
+        walk_syn(self, node)
+    }
+
+    fn visit_fact(&mut self, node: &'a Fact<'a>) {
+        walk_fact(self, node)
+    }
+
+    fn visit_ident(&mut self, _node: &'a Ident<'a>) {}
+
+    fn visit_directive(&mut self, node: &'a Directive<'a>) {
+        walk_directive(self, node)
+    }
+}
+
+pub fn walk_syn<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &'a Syn<'a>) {
+    match node {
+        Syn::Fact(f) => v.visit_fact(f),
+        Syn::Directive(d) => v.visit_directive(d),
+    }
+}
+
+pub fn walk_fact<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &'a Fact<'a>) {
+    match node {
+        Fact::Atom(i) => v.visit_ident(i),
+        Fact::Fact(i, children) => {
+            v.visit_ident(i);
+            for child in children {
+                v.visit_fact(child);
+            }
+        },
+    }
+}
+
+pub fn walk_directive<'a, V: Visit<'a> + ?Sized>(_v: &mut V, _node: &'a Directive<'a>) {
+    // `Directive`'s fields aren't visible from here; nothing to descend into.
+}
+
+pub trait VisitMut {
+    fn visit_syn_mut(&mut self, node: &mut Syn) {
+        walk_syn_mut(self, node)
+    }
+
+    fn visit_fact_mut(&mut self, node: &mut Fact) {
+        walk_fact_mut(self, node)
+    }
+
+    fn visit_ident_mut(&mut self, _node: &mut Ident) {}
+
+    fn visit_directive_mut(&mut self, node: &mut Directive) {
+        walk_directive_mut(self, node)
+    }
+}
+
+pub fn walk_syn_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Syn) {
+    match node {
+        Syn::Fact(f) => v.visit_fact_mut(f),
+        Syn::Directive(d) => v.visit_directive_mut(d),
+    }
+}
+
+pub fn walk_fact_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Fact) {
+    match node {
+        Fact::Atom(i) => v.visit_ident_mut(i),
+        Fact::Fact(i, children) => {
+            v.visit_ident_mut(i);
+            for child in children {
+                v.visit_fact_mut(child);
+            }
+        },
+    }
+}
+
+pub fn walk_directive_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut Directive) {}
+
+pub trait Fold {
+    fn fold_syn(&mut self, node: Syn) -> Syn {
+        fold_syn_helper(self, node)
+    }
+
+    fn fold_fact(&mut self, node: Fact) -> Fact {
+        fold_fact_helper(self, node)
+    }
+
+    fn fold_ident(&mut self, node: Ident) -> Ident {
+        node
+    }
+
+    fn fold_directive(&mut self, node: Directive) -> Directive {
+        fold_directive_helper(self, node)
+    }
+}
+
+pub fn fold_syn_helper<F: Fold + ?Sized>(f: &mut F, node: Syn) -> Syn {
+    match node {
+        Syn::Fact(fact) => Syn::Fact(f.fold_fact(fact)),
+        Syn::Directive(d) => Syn::Directive(f.fold_directive(d)),
+    }
+}
+
+pub fn fold_fact_helper<F: Fold + ?Sized>(f: &mut F, node: Fact) -> Fact {
+    match node {
+        Fact::Atom(i) => Fact::Atom(f.fold_ident(i)),
+        Fact::Fact(i, children) => {
+            let i = f.fold_ident(i);
+            let children = children.into_iter().map(|c| f.fold_fact(c)).collect();
+            Fact::Fact(i, children)
+        },
+    }
+}
+
+pub fn fold_directive_helper<F: Fold + ?Sized>(_f: &mut F, node: Directive) -> Directive {
+    node
+}
+
+/// Example `Visit` pass: collect every `Ident` encountered in source order.
+/// Demonstrates that adding a new pass no longer requires a bespoke
+/// recursion, just a handful of trait methods.
+#[derive(Default)]
+pub struct IdentCollector<'a> {
+    pub idents: Vec<&'a Ident<'a>>,
+}
+
+impl<'a> Visit<'a> for IdentCollector<'a> {
+    fn visit_ident(&mut self, node: &'a Ident<'a>) {
+        self.idents.push(node);
+    }
+}